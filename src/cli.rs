@@ -1,22 +1,33 @@
-use clap::Parser;
+use crate::core::output::OutputFormat;
+use clap::{Parser, Subcommand};
 
 #[derive(Parser, Debug)]
 #[command(name = "statusline")]
 #[command(version = "0.1.0")]
-#[command(
-    about = "statusline - High-performance Claude Code StatusLine tool written in Rust"
-)]
+#[command(about = "statusline - High-performance Claude Code StatusLine tool written in Rust")]
 #[command(
     long_about = "statusline v0.1.0\nA high-performance Claude Code StatusLine tool written in Rust.\nProvides real-time usage tracking, Git integration, and customizable segments."
 )]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Configuration file path
     #[arg(short, long)]
     pub config: Option<String>,
 
-    /// Theme selection
-    #[arg(short, long, default_value = "dark")]
-    pub theme: String,
+    /// Theme selection (dark, light, solarized, high-contrast, mono or a
+    /// custom theme file name); overrides `theme` in config.toml
+    #[arg(short, long)]
+    pub theme: Option<String>,
+
+    /// Output format: ansi (Claude Code), plain, json, tmux, waybar or i3bar
+    #[arg(short, long, default_value = "ansi", value_parser = parse_format)]
+    pub format: String,
+
+    /// Render in this process even when a daemon is running
+    #[arg(long)]
+    pub no_daemon: bool,
 
     /// Enable TUI configuration mode
     #[arg(long)]
@@ -31,8 +42,34 @@ pub struct Cli {
     pub validate: bool,
 }
 
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Serve renders over a Unix socket, keeping API, git and network data
+    /// warm between renders
+    Daemon,
+    /// Inspect the PackyCode JWT
+    Token {
+        #[command(subcommand)]
+        command: TokenCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TokenCommand {
+    /// Print the token's claims and expiry time (decoded locally, not verified)
+    Check,
+}
+
 impl Cli {
     pub fn parse_args() -> Self {
         Self::parse()
     }
 }
+
+/// Accept the names and aliases `OutputFormat::from_name` knows, so a
+/// mistyped `--format` fails instead of falling back to ANSI
+fn parse_format(name: &str) -> Result<String, String> {
+    OutputFormat::from_name(name)
+        .map(|_| name.to_string())
+        .ok_or_else(|| format!("可选: {}", OutputFormat::NAMES.join(", ")))
+}
@@ -0,0 +1,226 @@
+// Claude Code 的 API 设置（settings.json、环境变量）与 PackyCode 后端接口地址
+use super::types::PackyCodeConfig;
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+
+const DEFAULT_API_BASE_URL: &str = "https://api.anthropic.com";
+const DEFAULT_PACKYCODE_BASE_URL: &str = "https://share.packycode.com";
+pub const DEFAULT_USER_INFO_PATH: &str = "/api/backend/users/info";
+pub const DEFAULT_PEER_SPENDING_PATH: &str = "/api/backend/accounts/peer-spending/today";
+
+#[derive(Debug, Deserialize)]
+struct ClaudeCodeSettings {
+    env: Option<ClaudeCodeEnv>,
+    info_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClaudeCodeEnv {
+    #[serde(rename = "ANTHROPIC_BASE_URL")]
+    base_url: Option<String>,
+    #[serde(rename = "ANTHROPIC_AUTH_TOKEN")]
+    auth_token: Option<String>,
+    #[serde(rename = "ANTHROPIC_API_KEY")]
+    api_key: Option<String>,
+}
+
+/// API key and base URL Claude Code talks to
+#[derive(Debug, Clone)]
+pub struct ApiSettings {
+    pub api_key: Option<String>,
+    pub base_url: String,
+    /// Full users/info URL from settings.json or `$INFO_URL`
+    pub info_url: Option<String>,
+}
+
+impl ApiSettings {
+    pub fn load() -> Self {
+        // Try multiple sources for API configuration
+
+        // 1. Claude Code settings.json
+        if let Some(config_dir) = Self::get_claude_config_dir() {
+            let settings_path = config_dir.join("settings.json");
+            if let Ok(content) = fs::read_to_string(&settings_path) {
+                if let Ok(settings) = serde_json::from_str::<ClaudeCodeSettings>(&content) {
+                    let info_url = settings.info_url.clone();
+                    if let Some(env) = settings.env {
+                        let api_key = env.auth_token.or(env.api_key);
+                        let base_url = env
+                            .base_url
+                            .unwrap_or_else(|| DEFAULT_API_BASE_URL.to_string());
+                        if api_key.is_some() {
+                            return Self {
+                                api_key,
+                                base_url,
+                                info_url,
+                            };
+                        }
+                    }
+                }
+            }
+        }
+
+        // 2. Environment variable
+        let api_key = std::env::var("ANTHROPIC_API_KEY")
+            .ok()
+            .or_else(|| std::env::var("ANTHROPIC_AUTH_TOKEN").ok());
+
+        let base_url = std::env::var("ANTHROPIC_BASE_URL")
+            .unwrap_or_else(|_| DEFAULT_API_BASE_URL.to_string());
+
+        let info_url = std::env::var("INFO_URL").ok();
+
+        // 3. Claude Code api_key file
+        if api_key.is_none() {
+            if let Some(home) = dirs::home_dir() {
+                let config_path = home.join(".claude").join("api_key");
+                if let Ok(key) = fs::read_to_string(config_path) {
+                    return Self {
+                        api_key: Some(key.trim().to_string()),
+                        base_url,
+                        info_url,
+                    };
+                }
+            }
+        }
+
+        Self {
+            api_key,
+            base_url,
+            info_url,
+        }
+    }
+
+    fn get_claude_config_dir() -> Option<PathBuf> {
+        // Claude Code config directory is ~/.claude
+        dirs::home_dir().map(|home| home.join(".claude"))
+    }
+}
+
+/// Full URLs of the PackyCode backend requests
+#[derive(Debug, Clone)]
+pub struct PackyCodeEndpoints {
+    /// users/info for the quota segment, authenticated with the API key
+    pub user_info: String,
+    /// Ranking requests, authenticated with the JWT
+    pub peer_spending: String,
+    pub ranking_user_info: String,
+}
+
+impl Default for PackyCodeEndpoints {
+    fn default() -> Self {
+        PackyCodeConfig::default().endpoints(DEFAULT_API_BASE_URL, None)
+    }
+}
+
+impl PackyCodeConfig {
+    /// Endpoints for `[packycode]`. An empty `base_url` follows the API base
+    /// URL Claude Code uses; an explicit `info_url` still wins for users/info.
+    pub fn endpoints(&self, api_base_url: &str, info_url: Option<&str>) -> PackyCodeEndpoints {
+        let api_base_url = api_base_url.trim_end_matches('/');
+        let (info_base, peer_base) = if !self.base_url.is_empty() {
+            let base = self.base_url.trim_end_matches('/');
+            (base, base)
+        } else if api_base_url.starts_with("https://share-api") {
+            // share-api 的后端在 share.packycode.com
+            (DEFAULT_PACKYCODE_BASE_URL, DEFAULT_PACKYCODE_BASE_URL)
+        } else if api_base_url.contains("packycode.com") {
+            // 公交车模式：后端与 API 同域
+            (api_base_url, api_base_url)
+        } else {
+            // 其他中转站沿用同域的 users/info，API key 不发往其他域名；排名只有 PackyCode 提供
+            (api_base_url, DEFAULT_PACKYCODE_BASE_URL)
+        };
+
+        PackyCodeEndpoints {
+            user_info: info_url
+                .map(str::to_string)
+                .unwrap_or_else(|| join_path(info_base, &self.user_info_path)),
+            peer_spending: join_path(peer_base, &self.peer_spending_path),
+            ranking_user_info: join_path(peer_base, &self.user_info_path),
+        }
+    }
+}
+
+fn join_path(base: &str, path: &str) -> String {
+    if path.is_empty() {
+        return base.to_string();
+    }
+    format!("{}/{}", base, path.trim_start_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoints(base_url: &str, api_base_url: &str, info_url: Option<&str>) -> PackyCodeEndpoints {
+        PackyCodeConfig {
+            base_url: base_url.to_string(),
+            ..PackyCodeConfig::default()
+        }
+        .endpoints(api_base_url, info_url)
+    }
+
+    #[test]
+    fn share_api_uses_share_backend() {
+        let endpoints = endpoints("", "https://share-api.packycode.com/", None);
+        assert_eq!(
+            endpoints.user_info,
+            "https://share.packycode.com/api/backend/users/info"
+        );
+        assert_eq!(
+            endpoints.peer_spending,
+            "https://share.packycode.com/api/backend/accounts/peer-spending/today"
+        );
+    }
+
+    #[test]
+    fn packycode_hosts_use_same_origin() {
+        let endpoints = endpoints("", "https://api.packycode.com", None);
+        assert_eq!(
+            endpoints.user_info,
+            "https://api.packycode.com/api/backend/users/info"
+        );
+        assert_eq!(
+            endpoints.ranking_user_info,
+            "https://api.packycode.com/api/backend/users/info"
+        );
+    }
+
+    #[test]
+    fn other_relays_keep_ranking_on_packycode() {
+        let endpoints = endpoints("", "https://relay.example.com", None);
+        assert_eq!(
+            endpoints.user_info,
+            "https://relay.example.com/api/backend/users/info"
+        );
+        assert!(endpoints
+            .peer_spending
+            .starts_with(DEFAULT_PACKYCODE_BASE_URL));
+        assert!(endpoints
+            .ranking_user_info
+            .starts_with(DEFAULT_PACKYCODE_BASE_URL));
+    }
+
+    #[test]
+    fn base_url_and_info_url_override() {
+        let endpoints = endpoints(
+            "http://127.0.0.1:8080/",
+            "https://share-api.packycode.com",
+            Some("https://info.example.com/me"),
+        );
+        assert_eq!(endpoints.user_info, "https://info.example.com/me");
+        assert_eq!(
+            endpoints.ranking_user_info,
+            "http://127.0.0.1:8080/api/backend/users/info"
+        );
+    }
+
+    #[test]
+    fn join_path_normalizes_slashes() {
+        assert_eq!(join_path("http://h", "/a/b"), "http://h/a/b");
+        assert_eq!(join_path("http://h", "a/b"), "http://h/a/b");
+        assert_eq!(join_path("http://h", ""), "http://h");
+    }
+}
@@ -1,9 +1,21 @@
-use super::types::{Config, SegmentsConfig};
+use super::api::{DEFAULT_PEER_SPENDING_PATH, DEFAULT_USER_INFO_PATH};
+use super::types::{
+    AnthropicQuotaConfig, BarsConfig, CacheConfig, ColorStop, Config, CustomQuotaConfig,
+    DaemonConfig, LayoutConfig, OneApiQuotaConfig, PackyCodeConfig, QuotaConfig, SegmentsConfig,
+    TimingConfig,
+};
+use std::collections::BTreeMap;
 
 pub const DEFAULT_CONFIG: Config = Config {
-    theme: String::new(), // Set to "dark" at runtime
+    theme: String::new(),      // Set to "dark" at runtime
+    icons: String::new(),      // Set to "emoji" at runtime
+    color_mode: String::new(), // Set to "auto" at runtime
+    hyperlinks: String::new(), // Set to "auto" at runtime
     first_run: true,
     jwt_token: None, // JWT token to be set by user
+    jwt_token_file: String::new(),
+    jwt_token_command: String::new(),
+    jwt_expiry_warning_days: 3,
     segments: SegmentsConfig {
         directory: true,
         git: true,
@@ -14,7 +26,75 @@ pub const DEFAULT_CONFIG: Config = Config {
         emoji: false,
         spinner: true,
         network: false, // Network segment disabled by default
-        ranking: true, // Ranking segment enabled by default
+        ranking: true,  // Ranking segment enabled by default
+        token: true,
+        monthly: false,
+    },
+    layout: LayoutConfig {
+        mode: String::new(),            // Set to "plain" at runtime
+        powerline_style: String::new(), // Set to "sharp" at runtime
+        order: Vec::new(),              // Empty means the default order
+        separator: String::new(),       // Set to " | " at runtime
+        separator_color: String::new(), // Use the theme color
+        padding: 0,
+        segment_padding: BTreeMap::new(),
+        max_width: 0, // Detect from the terminal
+        rows: Vec::new(),
+    },
+    colors: BTreeMap::new(),
+    formats: BTreeMap::new(),
+    bars: BarsConfig {
+        usage: false,
+        quota: false,
+        width: 10,
+        style: String::new(),    // Set to "blocks" at runtime
+        color_stops: Vec::new(), // Set to green/yellow/red at runtime
+    },
+    daemon: DaemonConfig {
+        socket: String::new(),
+        timeout_ms: 500,
+        refresh_interval: 10,
+    },
+    timing: TimingConfig {
+        deadline_ms: 300,
+        segment_timeouts: BTreeMap::new(),
+    },
+    cache: CacheConfig {
+        dir: String::new(),
+        ttl: BTreeMap::new(),
+        max_stale: 3600,
+        lock_wait_ms: 1000,
+    },
+    packycode: PackyCodeConfig {
+        base_url: String::new(),
+        user_info_path: String::new(), // Set to "/api/backend/users/info" at runtime
+        peer_spending_path: String::new(), // Set to "/api/backend/accounts/peer-spending/today" at runtime
+    },
+    quota: QuotaConfig {
+        provider: String::new(), // Set to "auto" at runtime
+        anthropic: AnthropicQuotaConfig {
+            admin_key: String::new(),
+            workspace_id: String::new(),
+            base_url: String::new(),
+            daily_budget: 0.0,
+            monthly_budget: 0.0,
+        },
+        oneapi: OneApiQuotaConfig {
+            mode: String::new(), // Set to "billing" at runtime
+            base_url: String::new(),
+            access_token: String::new(),
+            user_id: 0,
+            quota_per_unit: 500000.0,
+        },
+        custom: CustomQuotaConfig {
+            url: String::new(),
+            method: String::new(),      // Set to "GET" at runtime
+            auth_header: String::new(), // Set to "Authorization: Bearer {api_key}" at runtime
+            spent: String::new(),
+            budget: String::new(),
+            remaining: String::new(),
+            currency: String::new(),
+        },
     },
 };
 
@@ -22,8 +102,14 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             theme: "dark".to_string(),
+            icons: "emoji".to_string(),
+            color_mode: "auto".to_string(),
+            hyperlinks: "auto".to_string(),
             first_run: true,
             jwt_token: None, // JWT token to be set by user
+            jwt_token_file: String::new(),
+            jwt_token_command: String::new(),
+            jwt_expiry_warning_days: 3,
             segments: SegmentsConfig {
                 directory: true,
                 git: true,
@@ -34,8 +120,140 @@ impl Default for Config {
                 emoji: false,
                 spinner: true,
                 network: false, // Network segment disabled by default
-                ranking: true, // Ranking segment enabled by default
+                ranking: true,  // Ranking segment enabled by default
+                token: true,
+                monthly: false,
             },
+            layout: LayoutConfig::default(),
+            colors: BTreeMap::new(),
+            formats: BTreeMap::new(),
+            bars: BarsConfig::default(),
+            daemon: DaemonConfig::default(),
+            timing: TimingConfig::default(),
+            cache: CacheConfig::default(),
+            packycode: PackyCodeConfig::default(),
+            quota: QuotaConfig::default(),
+        }
+    }
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        LayoutConfig {
+            mode: "plain".to_string(),
+            powerline_style: "sharp".to_string(),
+            order: Vec::new(), // Empty means the default order
+            separator: " | ".to_string(),
+            separator_color: String::new(), // Use the theme color
+            padding: 0,
+            segment_padding: BTreeMap::new(),
+            max_width: 0, // Detect from the terminal
+            rows: Vec::new(),
+        }
+    }
+}
+
+impl Default for BarsConfig {
+    fn default() -> Self {
+        let stop = |at: f64, color: &str| ColorStop {
+            at,
+            color: color.to_string(),
+        };
+        BarsConfig {
+            usage: false,
+            quota: false,
+            width: 10,
+            style: "blocks".to_string(),
+            color_stops: vec![stop(0.0, "green"), stop(60.0, "yellow"), stop(85.0, "red")],
+        }
+    }
+}
+
+impl Default for DaemonConfig {
+    fn default() -> Self {
+        DaemonConfig {
+            socket: String::new(),
+            timeout_ms: 500,
+            refresh_interval: 10,
+        }
+    }
+}
+
+impl Default for TimingConfig {
+    fn default() -> Self {
+        TimingConfig {
+            deadline_ms: 300,
+            segment_timeouts: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            dir: String::new(),
+            ttl: BTreeMap::new(),
+            max_stale: 3600,
+            lock_wait_ms: 1000,
+        }
+    }
+}
+
+impl Default for PackyCodeConfig {
+    fn default() -> Self {
+        PackyCodeConfig {
+            base_url: String::new(),
+            user_info_path: DEFAULT_USER_INFO_PATH.to_string(),
+            peer_spending_path: DEFAULT_PEER_SPENDING_PATH.to_string(),
+        }
+    }
+}
+
+impl Default for QuotaConfig {
+    fn default() -> Self {
+        QuotaConfig {
+            provider: "auto".to_string(),
+            anthropic: AnthropicQuotaConfig::default(),
+            oneapi: OneApiQuotaConfig::default(),
+            custom: CustomQuotaConfig::default(),
+        }
+    }
+}
+
+impl Default for AnthropicQuotaConfig {
+    fn default() -> Self {
+        AnthropicQuotaConfig {
+            admin_key: String::new(),
+            workspace_id: String::new(),
+            base_url: String::new(),
+            daily_budget: 0.0,
+            monthly_budget: 0.0,
+        }
+    }
+}
+
+impl Default for OneApiQuotaConfig {
+    fn default() -> Self {
+        OneApiQuotaConfig {
+            mode: "billing".to_string(),
+            base_url: String::new(),
+            access_token: String::new(),
+            user_id: 0,
+            quota_per_unit: 500000.0, // one-api 默认 500000 额度 = $1
+        }
+    }
+}
+
+impl Default for CustomQuotaConfig {
+    fn default() -> Self {
+        CustomQuotaConfig {
+            url: String::new(),
+            method: "GET".to_string(),
+            auth_header: "Authorization: Bearer {api_key}".to_string(),
+            spent: String::new(),
+            budget: String::new(),
+            remaining: String::new(),
+            currency: String::new(),
         }
     }
 }
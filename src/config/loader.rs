@@ -1,6 +1,10 @@
 use super::types::Config;
-use std::path::{Path, PathBuf};
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+// --config 指定的配置文件；主题与 token 文件都以它所在目录为准
+static ACTIVE_CONFIG: OnceLock<PathBuf> = OnceLock::new();
 
 pub struct ConfigLoader;
 
@@ -16,9 +20,24 @@ impl ConfigLoader {
         PathBuf::from("config.toml")
     }
 
+    /// Directory holding the active config file (`--config`, or config.toml
+    /// next to the executable); custom themes live in its `themes/` subdirectory
+    pub fn config_dir() -> PathBuf {
+        let active = ACTIVE_CONFIG.get().cloned();
+        active
+            .unwrap_or_else(Self::get_config_path)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default()
+    }
+
+    pub fn themes_dir() -> PathBuf {
+        Self::config_dir().join("themes")
+    }
+
     pub fn load() -> Config {
         let config_path = Self::get_config_path();
-        
+
         if config_path.exists() {
             match Self::load_from_path(&config_path) {
                 Ok(mut config) => {
@@ -26,29 +45,45 @@ impl ConfigLoader {
                     config.first_run = false;
                     config
                 }
-                Err(_e) => {
-                    Config::default()
-                }
+                Err(_e) => Config::default(),
             }
         } else {
             // 配置文件不存在，创建默认配置并保存
-            let mut config = Config::default();
-            config.first_run = true;
-            
+            // 默认配置即为首次运行
+            let config = Config::default();
+
             // 创建配置目录
             if let Some(parent) = config_path.parent() {
                 let _ = fs::create_dir_all(parent);
             }
-            
+
             // 保存配置文件，标记为非首次运行
             let mut save_config = config.clone();
             save_config.first_run = false;
             let _ = Self::save_config(&save_config, &config_path);
-            
+
             config
         }
     }
 
+    /// `--config` file when given, otherwise config.toml next to the executable
+    pub fn load_from(path: Option<&Path>) -> Config {
+        let Some(path) = path else {
+            return Self::load();
+        };
+        let _ = ACTIVE_CONFIG.set(path.to_path_buf());
+        match Self::load_from_path(path) {
+            Ok(mut config) => {
+                config.first_run = false;
+                config
+            }
+            Err(e) => {
+                eprintln!("statusline: {}: {}", path.display(), e);
+                Config::default()
+            }
+        }
+    }
+
     pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<Config, Box<dyn std::error::Error>> {
         let content = fs::read_to_string(path)?;
         let config: Config = toml::from_str(&content)?;
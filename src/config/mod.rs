@@ -1,7 +1,11 @@
+pub mod api;
 pub mod defaults;
 pub mod loader;
+pub mod token;
 pub mod types;
 
+pub use api::{ApiSettings, PackyCodeEndpoints};
 pub use defaults::DEFAULT_CONFIG;
 pub use loader::ConfigLoader;
+pub use token::{JwtClaims, JwtTokenSources, SharedJwtToken, TokenError};
 pub use types::*;
@@ -0,0 +1,462 @@
+// PackyCode JWT 的来源：配置、环境变量、权限为 600 的密钥文件、外部命令
+use super::loader::ConfigLoader;
+use super::types::Config;
+use crate::core::cache::MemoryCache;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+pub const TOKEN_ENV: &str = "PACKYCODE_JWT_TOKEN";
+const DEFAULT_TOKEN_FILE: &str = "jwt_token";
+
+// 命令输出缓存 5 分钟，避免每次渲染都调用 pass 之类的工具
+static COMMAND_CACHE: MemoryCache<String> = MemoryCache::new(Duration::from_secs(300));
+
+/// Places the PackyCode JWT is looked up, in order: `jwt_token` in
+/// config.toml, `$PACKYCODE_JWT_TOKEN`, the secrets file, and the stdout of
+/// `jwt_token_command`
+#[derive(Debug, Clone, Default)]
+pub struct JwtTokenSources {
+    pub token: Option<String>,
+    /// Secrets file; `None` uses `jwt_token` next to config.toml if present
+    pub file: Option<PathBuf>,
+    pub command: Option<String>,
+}
+
+/// Why no token could be used
+#[derive(Debug, Clone)]
+pub enum TokenError {
+    Missing,
+    /// Secrets file readable by group or others (unix mode)
+    InsecureFile(PathBuf, u32),
+    Unreadable(PathBuf, String),
+    CommandFailed(String),
+    /// The token's `exp` claim has passed
+    Expired,
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenError::Missing => write!(f, "未配置 JWT token"),
+            TokenError::InsecureFile(path, mode) => {
+                write!(f, "{} 权限为 {:o}，需要 600", path.display(), mode)
+            }
+            TokenError::Unreadable(path, err) => write!(f, "无法读取 {}: {}", path.display(), err),
+            TokenError::CommandFailed(err) => write!(f, "jwt_token_command 失败: {}", err),
+            TokenError::Expired => write!(f, "token expired"),
+        }
+    }
+}
+
+impl std::error::Error for TokenError {}
+
+impl JwtTokenSources {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            token: config
+                .jwt_token
+                .clone()
+                .filter(|token| !token.trim().is_empty()),
+            file: Some(config.jwt_token_file.as_str())
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+            command: Some(config.jwt_token_command.clone()).filter(|command| !command.is_empty()),
+        }
+    }
+
+    /// The first token found. An explicitly configured file or command
+    /// that fails is reported rather than skipped.
+    pub fn resolve(&self) -> Result<String, TokenError> {
+        if let Some(token) = &self.token {
+            return Ok(token.trim().to_string());
+        }
+        if let Some(token) = std::env::var(TOKEN_ENV)
+            .ok()
+            .filter(|token| !token.trim().is_empty())
+        {
+            return Ok(token.trim().to_string());
+        }
+
+        match &self.file {
+            Some(path) => return read_token_file(path),
+            None => {
+                let path = ConfigLoader::config_dir().join(DEFAULT_TOKEN_FILE);
+                if path.exists() {
+                    return read_token_file(&path);
+                }
+            }
+        }
+
+        let Some(command) = &self.command else {
+            return Err(TokenError::Missing);
+        };
+        if let Some(token) = COMMAND_CACHE.get(command) {
+            return Ok(token);
+        }
+        let token = run_token_command(command)?;
+        COMMAND_CACHE.set(command, token.clone());
+        Ok(token)
+    }
+}
+
+/// A token resolved at most once and shared by the segments of one render,
+/// so `jwt_token_command` runs once even though segments render in parallel.
+/// Resolution happens on first use, on a segment's thread.
+#[derive(Debug, Clone, Default)]
+pub struct SharedJwtToken {
+    sources: JwtTokenSources,
+    token: Arc<OnceLock<Result<String, TokenError>>>,
+}
+
+impl SharedJwtToken {
+    pub fn new(sources: JwtTokenSources) -> Self {
+        Self {
+            sources,
+            token: Arc::new(OnceLock::new()),
+        }
+    }
+
+    pub fn resolve(&self) -> Result<String, TokenError> {
+        self.token.get_or_init(|| self.sources.resolve()).clone()
+    }
+}
+
+/// Claims of the JWT payload we care about
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct JwtClaims {
+    /// Expiry, Unix seconds
+    pub exp: Option<i64>,
+    /// Issued at, Unix seconds
+    pub iat: Option<i64>,
+    pub username: Option<String>,
+    pub email: Option<String>,
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+impl JwtClaims {
+    /// Decode the payload of `token` locally. The signature is not
+    /// verified; the claims are only used for display and expiry warnings.
+    pub fn decode(token: &str) -> Option<Self> {
+        let payload = token.split('.').nth(1)?;
+        let bytes = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    /// Seconds until `exp` at Unix time `now`; negative once expired
+    pub fn expires_in(&self, now: i64) -> Option<i64> {
+        self.exp.map(|exp| exp - now)
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_in(now).is_some_and(|secs| secs <= 0)
+    }
+}
+
+/// Compact duration for expiry text: `2d`, `5h`, `12m`
+pub fn format_remaining(secs: i64) -> String {
+    let secs = secs.max(0);
+    if secs >= 86400 {
+        format!("{}d", secs / 86400)
+    } else if secs >= 3600 {
+        format!("{}h", secs / 3600)
+    } else {
+        format!("{}m", (secs / 60).max(1))
+    }
+}
+
+fn read_token_file(path: &Path) -> Result<String, TokenError> {
+    check_permissions(path)?;
+    let content = fs::read_to_string(path)
+        .map_err(|err| TokenError::Unreadable(path.to_path_buf(), err.to_string()))?;
+    let token = content.trim();
+    if token.is_empty() {
+        return Err(TokenError::Unreadable(
+            path.to_path_buf(),
+            "文件为空".to_string(),
+        ));
+    }
+    Ok(token.to_string())
+}
+
+// 密钥文件只允许所有者读写
+#[cfg(unix)]
+fn check_permissions(path: &Path) -> Result<(), TokenError> {
+    use std::os::unix::fs::PermissionsExt;
+    let metadata = fs::metadata(path)
+        .map_err(|err| TokenError::Unreadable(path.to_path_buf(), err.to_string()))?;
+    let mode = metadata.permissions().mode() & 0o777;
+    if mode & 0o077 != 0 {
+        return Err(TokenError::InsecureFile(path.to_path_buf(), mode));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_path: &Path) -> Result<(), TokenError> {
+    Ok(())
+}
+
+fn run_token_command(command: &str) -> Result<String, TokenError> {
+    let output = if cfg!(windows) {
+        Command::new("cmd").args(["/C", command]).output()
+    } else {
+        Command::new("sh").args(["-c", command]).output()
+    }
+    .map_err(|err| TokenError::CommandFailed(err.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().next().unwrap_or("").trim().to_string();
+        return Err(TokenError::CommandFailed(if reason.is_empty() {
+            output.status.to_string()
+        } else {
+            reason
+        }));
+    }
+    // 只取第一行，pass 等工具会在后面附带其他字段
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.lines().next().map(str::trim) {
+        Some(token) if !token.is_empty() => Ok(token.to_string()),
+        _ => Err(TokenError::CommandFailed("没有输出".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jwt(payload: &str) -> String {
+        format!(
+            "{}.{}.sig",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#),
+            URL_SAFE_NO_PAD.encode(payload)
+        )
+    }
+
+    #[test]
+    fn decode_reads_claims() {
+        let claims = JwtClaims::decode(&jwt(r#"{"exp":2000,"username":"tester"}"#)).unwrap();
+        assert_eq!(claims.exp, Some(2000));
+        assert_eq!(claims.username.as_deref(), Some("tester"));
+        assert!(claims.permissions.is_empty());
+    }
+
+    #[test]
+    fn decode_accepts_padded_payload() {
+        let payload = base64::engine::general_purpose::URL_SAFE.encode(r#"{"exp":1}"#);
+        let claims = JwtClaims::decode(&format!("h.{}.s", payload)).unwrap();
+        assert_eq!(claims.exp, Some(1));
+    }
+
+    #[test]
+    fn decode_rejects_malformed_tokens() {
+        assert!(JwtClaims::decode("").is_none());
+        assert!(JwtClaims::decode("no-dots").is_none());
+        assert!(JwtClaims::decode("h.!!!.s").is_none());
+        assert!(JwtClaims::decode(&jwt("not json")).is_none());
+        assert!(JwtClaims::decode(&jwt(r#"{"exp":"soon"}"#)).is_none());
+    }
+
+    #[test]
+    fn expiry_relative_to_now() {
+        let claims = JwtClaims::decode(&jwt(r#"{"exp":1000}"#)).unwrap();
+        assert_eq!(claims.expires_in(400), Some(600));
+        assert!(!claims.is_expired(999));
+        assert!(claims.is_expired(1000));
+        assert!(claims.is_expired(5000));
+
+        let no_exp = JwtClaims::decode(&jwt("{}")).unwrap();
+        assert_eq!(no_exp.expires_in(0), None);
+        assert!(!no_exp.is_expired(i64::MAX));
+    }
+
+    #[test]
+    fn format_remaining_units() {
+        assert_eq!(format_remaining(3 * 86400 + 5), "3d");
+        assert_eq!(format_remaining(5 * 3600), "5h");
+        assert_eq!(format_remaining(12 * 60), "12m");
+        assert_eq!(format_remaining(10), "1m");
+        assert_eq!(format_remaining(-30), "1m");
+    }
+
+    // 环境变量是进程级的，读写它的测试依次运行
+    static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    fn with_env<T>(value: Option<&str>, f: impl FnOnce() -> T) -> T {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        match value {
+            Some(value) => std::env::set_var(TOKEN_ENV, value),
+            None => std::env::remove_var(TOKEN_ENV),
+        }
+        let result = f();
+        std::env::remove_var(TOKEN_ENV);
+        result
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("ccline-token-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[cfg(unix)]
+    fn write_token_file(path: &Path, content: &str, mode: u32) {
+        use std::os::unix::fs::PermissionsExt;
+        fs::write(path, content).unwrap();
+        fs::set_permissions(path, fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[test]
+    fn config_token_comes_first() {
+        let sources = JwtTokenSources {
+            token: Some(" from-config \n".to_string()),
+            file: Some(PathBuf::from("/nonexistent/jwt_token")),
+            command: Some("false".to_string()),
+        };
+        let token = with_env(Some("from-env"), || sources.resolve());
+        assert_eq!(token.unwrap(), "from-config");
+    }
+
+    #[test]
+    fn env_comes_before_file_and_command() {
+        let sources = JwtTokenSources {
+            token: None,
+            file: Some(PathBuf::from("/nonexistent/jwt_token")),
+            command: Some("false".to_string()),
+        };
+        let token = with_env(Some(" from-env "), || sources.resolve());
+        assert_eq!(token.unwrap(), "from-env");
+
+        // 空白的环境变量视为未设置
+        let err = with_env(Some("  "), || sources.resolve()).unwrap_err();
+        assert!(matches!(err, TokenError::Unreadable(..)), "{:?}", err);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_comes_before_command() {
+        let dir = temp_dir("file");
+        let path = dir.join("jwt_token");
+        write_token_file(&path, "from-file\n", 0o600);
+        let sources = JwtTokenSources {
+            token: None,
+            file: Some(path),
+            command: Some("echo from-command".to_string()),
+        };
+
+        let token = with_env(None, || sources.resolve());
+
+        assert_eq!(token.unwrap(), "from-file");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_readable_by_others_is_rejected() {
+        let dir = temp_dir("insecure");
+        let path = dir.join("jwt_token");
+        write_token_file(&path, "from-file", 0o644);
+        let sources = JwtTokenSources {
+            file: Some(path.clone()),
+            ..JwtTokenSources::default()
+        };
+
+        let err = with_env(None, || sources.resolve()).unwrap_err();
+
+        assert!(
+            matches!(&err, TokenError::InsecureFile(p, 0o644) if *p == path),
+            "{:?}",
+            err
+        );
+        assert!(err.to_string().contains("644"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn empty_file_is_unreadable() {
+        let dir = temp_dir("empty");
+        let path = dir.join("jwt_token");
+        write_token_file(&path, "\n", 0o600);
+        let sources = JwtTokenSources {
+            file: Some(path),
+            ..JwtTokenSources::default()
+        };
+
+        let err = with_env(None, || sources.resolve()).unwrap_err();
+
+        assert!(matches!(err, TokenError::Unreadable(..)), "{:?}", err);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_output_is_cached() {
+        let dir = temp_dir("command");
+        let runs = dir.join("runs");
+        // 每次运行追加一行，第二行起的内容不属于 token
+        let command = format!(
+            "echo run >> '{}'; echo from-command; echo extra",
+            runs.display()
+        );
+        let sources = JwtTokenSources {
+            command: Some(command),
+            ..JwtTokenSources::default()
+        };
+
+        let first = with_env(None, || sources.resolve());
+        let second = with_env(None, || sources.resolve());
+
+        assert_eq!(first.unwrap(), "from-command");
+        assert_eq!(second.unwrap(), "from-command");
+        assert_eq!(fs::read_to_string(&runs).unwrap().lines().count(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failing_command_reports_stderr() {
+        let sources = JwtTokenSources {
+            command: Some("echo 'pass: not found' >&2; exit 1".to_string()),
+            ..JwtTokenSources::default()
+        };
+
+        let err = with_env(None, || sources.resolve()).unwrap_err();
+
+        assert!(
+            matches!(&err, TokenError::CommandFailed(reason) if reason == "pass: not found"),
+            "{:?}",
+            err
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn silent_command_fails() {
+        let sources = JwtTokenSources {
+            command: Some("true".to_string()),
+            ..JwtTokenSources::default()
+        };
+
+        let err = with_env(None, || sources.resolve()).unwrap_err();
+
+        assert!(matches!(err, TokenError::CommandFailed(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn nothing_configured_is_missing() {
+        let sources = JwtTokenSources::default();
+        let err = with_env(None, || sources.resolve()).unwrap_err();
+        assert!(matches!(err, TokenError::Missing), "{:?}", err);
+    }
+}
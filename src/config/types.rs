@@ -1,11 +1,67 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
     pub theme: String,
+    /// Icon set: "nerd", "emoji", "ascii" or "none"
+    #[serde(default = "default_icons")]
+    pub icons: String,
+    /// "auto" (detect from NO_COLOR/COLORTERM/TERM), "truecolor", "256", "16" or "none"
+    #[serde(default = "default_color_mode")]
+    pub color_mode: String,
+    /// OSC 8 clickable segments: "auto" (detect the terminal), "on" or "off"
+    #[serde(default = "default_hyperlinks")]
+    pub hyperlinks: String,
     pub segments: SegmentsConfig,
     pub first_run: bool,
     pub jwt_token: Option<String>,
+    /// Secrets file holding the JWT, readable only by its owner (mode 600);
+    /// empty uses `jwt_token` next to config.toml when it exists
+    #[serde(default)]
+    pub jwt_token_file: String,
+    /// Shell command printing the JWT, e.g. `pass show packycode/jwt`
+    #[serde(default)]
+    pub jwt_token_command: String,
+    /// Days before the JWT expires that the token segment starts warning
+    #[serde(default = "default_jwt_expiry_warning_days")]
+    pub jwt_expiry_warning_days: u64,
+    #[serde(default)]
+    pub layout: LayoutConfig,
+    /// Per-segment color overrides applied on top of the theme
+    #[serde(default)]
+    pub colors: BTreeMap<String, SegmentColorConfig>,
+    /// Per-segment format templates, e.g. `quota = "${spent:.2}[ #{rank}]"`
+    #[serde(default)]
+    pub formats: BTreeMap<String, String>,
+    #[serde(default)]
+    pub bars: BarsConfig,
+    #[serde(default)]
+    pub daemon: DaemonConfig,
+    #[serde(default)]
+    pub timing: TimingConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub packycode: PackyCodeConfig,
+    #[serde(default)]
+    pub quota: QuotaConfig,
+}
+
+fn default_jwt_expiry_warning_days() -> u64 {
+    3
+}
+
+fn default_icons() -> String {
+    "emoji".to_string()
+}
+
+fn default_color_mode() -> String {
+    "auto".to_string()
+}
+
+fn default_hyperlinks() -> String {
+    "auto".to_string()
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -20,26 +76,236 @@ pub struct SegmentsConfig {
     pub spinner: bool,
     pub network: bool,
     pub ranking: bool,
+    /// JWT expiry warning, shown only as expiry approaches
+    #[serde(default = "default_token_segment")]
+    pub token: bool,
+    /// Month-to-date spend against the monthly budget, with pacing
+    #[serde(default)]
+    pub monthly: bool,
+}
+
+fn default_token_segment() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LayoutConfig {
+    /// "plain" (separator between segments) or "powerline"
+    pub mode: String,
+    /// Powerline joint: "sharp", "rounded", "slanted" or "plain" (no Nerd Font glyphs)
+    pub powerline_style: String,
+    /// Segment names in display order. Enabled segments that are not listed
+    /// are appended in the default order; `ranking` is only shown when listed.
+    pub order: Vec<String>,
+    pub separator: String,
+    /// Empty uses the theme's separator color
+    pub separator_color: String,
+    /// Spaces added on both sides of every segment
+    pub padding: usize,
+    /// Per-segment padding, keyed by segment name
+    pub segment_padding: BTreeMap<String, usize>,
+    /// Maximum line width in cells; 0 uses `COLUMNS` or the terminal size
+    pub max_width: usize,
+    /// Multi-line layout; when empty a single row built from `order` is used
+    pub rows: Vec<RowConfig>,
+}
+
+/// One line of a multi-line statusline (`[[layout.rows]]`)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RowConfig {
+    pub segments: Vec<String>,
+    /// Falls back to `layout.separator` / `layout.separator_color`
+    pub separator: Option<String>,
+    pub separator_color: Option<String>,
+    /// "left", "center" or "right"; the latter two need a known width
+    pub align: String,
+}
+
+/// Progress bars for context usage and daily budget
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BarsConfig {
+    /// Show a context-window bar in the usage segment
+    pub usage: bool,
+    /// Show a daily-budget bar in the quota segment
+    pub quota: bool,
+    /// Bar width in cells
+    pub width: usize,
+    /// "blocks" (▏▎▍▌▋▊▉█) or "ascii" ([###---])
+    pub style: String,
+    /// Segment color by fill percentage; the highest stop reached wins
+    pub color_stops: Vec<ColorStop>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ColorStop {
+    /// Percentage (0–100) from which this color applies
+    pub at: f64,
+    pub color: String,
+}
+
+/// On-disk cache of API responses and network probes
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// Cache directory; empty uses `~/.cache/ccline`
+    pub dir: String,
+    /// Seconds a response stays fresh, by source: `quota` (30), `ranking`
+    /// (60), `network` (30). 0 disables caching for that source.
+    pub ttl: BTreeMap<String, u64>,
+    /// Seconds past the TTL an old response is still shown while it is
+    /// refreshed in the background
+    pub max_stale: u64,
+    /// Milliseconds to wait for another process that is refreshing the same
+    /// endpoint before falling back to the cached value
+    pub lock_wait_ms: u64,
+}
+
+/// PackyCode backend endpoints used by quota and ranking
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PackyCodeConfig {
+    /// Backend host, e.g. `https://share.packycode.com` or a local stub;
+    /// empty derives it from `ANTHROPIC_BASE_URL`
+    pub base_url: String,
+    pub user_info_path: String,
+    pub peer_spending_path: String,
+}
+
+/// Backend the quota segment reads spend and budget from
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct QuotaConfig {
+    /// "auto" (detect from `ANTHROPIC_BASE_URL`), "packycode", "anthropic",
+    /// "generic", "oneapi" or "custom"
+    pub provider: String,
+    pub anthropic: AnthropicQuotaConfig,
+    pub oneapi: OneApiQuotaConfig,
+    pub custom: CustomQuotaConfig,
+}
+
+/// Anthropic Admin API cost report (`provider = "anthropic"`)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AnthropicQuotaConfig {
+    /// Admin key (`sk-ant-admin...`); empty uses `$ANTHROPIC_ADMIN_KEY`
+    pub admin_key: String,
+    /// Only count this workspace ("default" for the Default workspace);
+    /// empty counts the whole organization
+    pub workspace_id: String,
+    /// API address; empty uses `https://api.anthropic.com`
+    pub base_url: String,
+    /// Budgets in USD; 0 shows spend without a budget
+    pub daily_budget: f64,
+    pub monthly_budget: f64,
+}
+
+/// Relays built on one-api or new-api (`provider = "oneapi"`)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct OneApiQuotaConfig {
+    /// "billing" (`/dashboard/billing/*` with the API key) or "user"
+    /// (`/api/user/self` with `access_token`)
+    pub mode: String,
+    /// Relay address; empty uses `ANTHROPIC_BASE_URL`
+    pub base_url: String,
+    /// System access token from the relay's settings page, for "user" mode
+    pub access_token: String,
+    /// new-api user id sent as `New-Api-User`; 0 omits the header
+    pub user_id: u64,
+    /// Quota units per dollar
+    pub quota_per_unit: f64,
+}
+
+/// Any JSON balance endpoint (`provider = "custom"`), mapped with JSON
+/// pointers such as `/data/used`. Amounts may be numbers or strings.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CustomQuotaConfig {
+    pub url: String,
+    /// "GET" or "POST"
+    pub method: String,
+    /// `Name: value` header with `{api_key}` replaced by the API key;
+    /// empty sends no credentials
+    pub auth_header: String,
+    pub spent: String,
+    pub budget: String,
+    pub remaining: String,
+    /// JSON pointer, or a fixed code such as "CNY"; empty is USD
+    pub currency: String,
+}
+
+/// Time budget of a render; segments run in parallel
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TimingConfig {
+    /// Overall deadline in milliseconds; 0 waits for every segment
+    pub deadline_ms: u64,
+    /// Per-segment limits in milliseconds, e.g. `{ network = 150 }`.
+    /// The overall deadline still applies.
+    pub segment_timeouts: BTreeMap<String, u64>,
+}
+
+/// Background renderer reached over a Unix socket (`statusline daemon`)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DaemonConfig {
+    /// Socket path; empty uses `$XDG_RUNTIME_DIR/ccline.sock` or a per-user
+    /// file in the temp directory
+    pub socket: String,
+    /// How long a client waits for the daemon before rendering in-process
+    pub timeout_ms: u64,
+    /// Seconds between background refreshes of network, quota and ranking
+    /// data; 0 disables them
+    pub refresh_interval: u64,
+}
+
+/// Colors for one segment, used both by `[colors.<segment>]` in config.toml
+/// and by `[segments.<segment>]` in theme files
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct SegmentColorConfig {
+    pub fg: Option<String>,
+    pub bg: Option<String>,
+    pub bold: Option<bool>,
 }
 
 // Data structures compatible with existing main.rs
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct Model {
     pub display_name: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct Workspace {
     pub current_dir: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct InputData {
     pub model: Model,
     pub workspace: Workspace,
     pub transcript_path: String,
 }
 
+impl InputData {
+    /// Input for runs outside Claude Code (tmux, desktop bars): the current
+    /// directory, without model or transcript
+    pub fn from_current_dir() -> Self {
+        let current_dir = std::env::current_dir()
+            .map(|dir| dir.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        InputData {
+            model: Model {
+                display_name: String::new(),
+            },
+            workspace: Workspace { current_dir },
+            transcript_path: String::new(),
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct Usage {
     pub input_tokens: u32,
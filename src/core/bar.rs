@@ -0,0 +1,93 @@
+// 进度条渲染：上下文占用、每日预算等
+use crate::config::BarsConfig;
+use crate::core::style::Color;
+
+// 1/8 到 7/8 格的块字符
+const PARTIAL_BLOCKS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Render `fraction` (0.0–1.0, clamped) as a bar `config.width` cells wide
+pub fn render_bar(fraction: f64, config: &BarsConfig) -> String {
+    let fraction = if fraction.is_finite() {
+        fraction.clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let width = config.width.max(1);
+
+    match config.style.as_str() {
+        "ascii" => {
+            let filled = (fraction * width as f64).round() as usize;
+            format!("[{}{}]", "#".repeat(filled), "-".repeat(width - filled))
+        }
+        _ => {
+            let eighths = (fraction * width as f64 * 8.0).round() as usize;
+            let (full, partial) = (eighths / 8, eighths % 8);
+            let mut bar = "█".repeat(full);
+            let mut used = full;
+            if partial > 0 && used < width {
+                bar.push(PARTIAL_BLOCKS[partial - 1]);
+                used += 1;
+            }
+            bar.push_str(&"░".repeat(width - used));
+            bar
+        }
+    }
+}
+
+/// Color of the highest stop at or below `percent`
+pub fn stop_color(percent: f64, config: &BarsConfig) -> Option<Color> {
+    config
+        .color_stops
+        .iter()
+        .filter(|stop| stop.at <= percent)
+        .max_by(|a, b| a.at.partial_cmp(&b.at).unwrap_or(std::cmp::Ordering::Equal))
+        .and_then(|stop| Color::from_name(&stop.color))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(style: &str, width: usize) -> BarsConfig {
+        BarsConfig {
+            width,
+            style: style.to_string(),
+            ..BarsConfig::default()
+        }
+    }
+
+    #[test]
+    fn empty_and_full_bars() {
+        assert_eq!(render_bar(0.0, &config("blocks", 4)), "░░░░");
+        assert_eq!(render_bar(1.0, &config("blocks", 4)), "████");
+        assert_eq!(render_bar(0.0, &config("ascii", 4)), "[----]");
+        assert_eq!(render_bar(1.0, &config("ascii", 4)), "[####]");
+    }
+
+    #[test]
+    fn out_of_range_fractions_are_clamped() {
+        assert_eq!(render_bar(-0.5, &config("blocks", 4)), "░░░░");
+        assert_eq!(render_bar(1.7, &config("blocks", 4)), "████");
+        assert_eq!(render_bar(f64::NAN, &config("ascii", 4)), "[----]");
+        assert_eq!(render_bar(f64::INFINITY, &config("ascii", 4)), "[----]");
+    }
+
+    #[test]
+    fn partial_blocks_keep_the_width() {
+        assert_eq!(render_bar(0.55, &config("blocks", 10)), "█████▌░░░░");
+        assert_eq!(render_bar(0.5, &config("ascii", 0)), "[#]");
+        for step in 0..=20 {
+            let bar = render_bar(step as f64 / 20.0, &config("blocks", 7));
+            assert_eq!(bar.chars().count(), 7, "{:?}", bar);
+        }
+    }
+
+    #[test]
+    fn stop_color_uses_highest_reached_stop() {
+        let config = BarsConfig::default();
+        assert_eq!(stop_color(0.0, &config), Some(Color::Green));
+        assert_eq!(stop_color(60.0, &config), Some(Color::Yellow));
+        assert_eq!(stop_color(99.0, &config), Some(Color::Red));
+        assert_eq!(stop_color(-1.0, &config), None);
+    }
+}
@@ -0,0 +1,469 @@
+// 缓存：进程内缓存在 daemon 模式下跨渲染保持数据，磁盘缓存在所有进程间共享
+use crate::config::CacheConfig;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::fs::TryLockError;
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+thread_local! {
+    // daemon 后台刷新时为 true：忽略未过期的缓存，重新获取并写回
+    static REFRESHING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `f` with every `MemoryCache` and `DiskCache` treated as expired, so that the values
+/// it fetches replace the cached ones. Used by the daemon's refresh thread.
+pub fn refreshing<T>(f: impl FnOnce() -> T) -> T {
+    REFRESHING.with(|flag| flag.set(true));
+    let result = f();
+    REFRESHING.with(|flag| flag.set(false));
+    result
+}
+
+/// Whether the current thread runs inside `refreshing`; worker threads
+/// spawned from it pass the flag on
+pub fn is_refreshing() -> bool {
+    REFRESHING.with(Cell::get)
+}
+
+/// Values by key, each valid for `ttl` after it was stored
+pub struct MemoryCache<T> {
+    entries: Mutex<BTreeMap<String, (T, Instant)>>,
+    ttl: Duration,
+}
+
+impl<T: Clone> MemoryCache<T> {
+    pub const fn new(ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(BTreeMap::new()),
+            ttl,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<T> {
+        if is_refreshing() {
+            return None;
+        }
+        let entries = self.entries.lock().ok()?;
+        let (value, stored) = entries.get(key)?;
+        (stored.elapsed() < self.ttl).then(|| value.clone())
+    }
+
+    pub fn set(&self, key: &str, value: T) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(key.to_string(), (value, Instant::now()));
+        }
+    }
+
+    /// Cached value, or the result of `fetch` (stored when it is `Some`)
+    pub fn get_or_fetch(&self, key: &str, fetch: impl FnOnce() -> Option<T>) -> Option<T> {
+        if let Some(value) = self.get(key) {
+            return Some(value);
+        }
+        let value = fetch()?;
+        self.set(key, value.clone());
+        Some(value)
+    }
+}
+
+// 后台刷新中的缓存文件，避免同一进程重复刷新
+static IN_FLIGHT: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+// 尚未完成的后台刷新与渲染线程数量，进程退出前等待它们写回
+static PENDING: Mutex<usize> = Mutex::new(0);
+static PENDING_DONE: Condvar = Condvar::new();
+// 等待其他进程释放锁时的轮询间隔
+const LOCK_POLL: Duration = Duration::from_millis(20);
+
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    /// Unix time in milliseconds
+    stored_at: u64,
+    value: T,
+}
+
+impl<T> Entry<T> {
+    fn age(&self) -> Duration {
+        Duration::from_millis(now_millis().saturating_sub(self.stored_at))
+    }
+}
+
+/// Responses cached on disk (`~/.cache/ccline` by default) and shared by
+/// every statusline process. Fresh for the source's TTL; after that the old
+/// value is still returned for up to `max_stale` while it is refreshed in
+/// the background (stale-while-revalidate).
+///
+/// Refreshes hold a lock file next to the entry, so that concurrent
+/// statusline processes fetch each endpoint once: the others wait up to
+/// `lock_wait` for the result, or keep showing the cached value.
+pub struct DiskCache {
+    dir: Option<PathBuf>,
+    source: &'static str,
+    ttl: Duration,
+    max_stale: Duration,
+    lock_wait: Duration,
+}
+
+impl DiskCache {
+    /// `source` names the data (quota, ranking, network) for `[cache] ttl`
+    pub fn new(config: &CacheConfig, source: &'static str, default_ttl: Duration) -> Self {
+        let dir = if config.dir.is_empty() {
+            dirs::cache_dir().map(|dir| dir.join("ccline"))
+        } else {
+            Some(PathBuf::from(&config.dir))
+        };
+        let ttl = config
+            .ttl
+            .get(source)
+            .map(|secs| Duration::from_secs(*secs))
+            .unwrap_or(default_ttl);
+        Self {
+            dir,
+            source,
+            ttl,
+            max_stale: Duration::from_secs(config.max_stale),
+            lock_wait: Duration::from_millis(config.lock_wait_ms),
+        }
+    }
+
+    /// Cached response of `endpoint` for `token`, or the result of `fetch`.
+    /// The token is only hashed into the file name, never stored.
+    pub fn get_or_fetch<T, F>(&self, endpoint: &str, token: &str, fetch: F) -> Option<T>
+    where
+        T: Serialize + DeserializeOwned + Send + 'static,
+        F: FnOnce() -> Option<T> + Send + 'static,
+    {
+        self.try_get_or_fetch(endpoint, token, move || fetch().ok_or(()))
+            .ok()
+    }
+
+    /// Like `get_or_fetch`, keeping the error of a failed `fetch`. Only
+    /// successful results are cached.
+    pub fn try_get_or_fetch<T, E, F>(
+        &self,
+        endpoint: &str,
+        token: &str,
+        fetch: F,
+    ) -> Result<T, FetchError<E>>
+    where
+        T: Serialize + DeserializeOwned + Send + 'static,
+        F: FnOnce() -> Result<T, E> + Send + 'static,
+    {
+        let Some(path) = self.path(endpoint, token).filter(|_| !self.ttl.is_zero()) else {
+            return fetch().map_err(FetchError::Failed);
+        };
+        let max_age = self.ttl + self.max_stale;
+        if !is_refreshing() {
+            match read_entry::<T>(&path) {
+                Some(entry) if entry.age() < self.ttl => return Ok(entry.value),
+                Some(entry) if entry.age() < max_age => {
+                    revalidate(path, self.ttl, max_age, fetch);
+                    return Ok(entry.value);
+                }
+                _ => {}
+            }
+        }
+        // 渲染超时后进程仍需等它写回缓存
+        let _pending = Pending::begin();
+        fetch_locked(&path, self.ttl, max_age, self.lock_wait, fetch)
+    }
+
+    fn path(&self, endpoint: &str, token: &str) -> Option<PathBuf> {
+        let key = fnv1a(&format!("{}\n{}", endpoint, token));
+        Some(
+            self.dir
+                .as_ref()?
+                .join(format!("{}-{:016x}.json", self.source, key)),
+        )
+    }
+}
+
+/// Why `DiskCache::try_get_or_fetch` has no value
+#[derive(Debug)]
+pub enum FetchError<E> {
+    /// Another process is fetching it and there is no usable cached copy
+    Busy,
+    Failed(E),
+}
+
+/// Work that `wait_for_revalidation` waits for, from `begin` until dropped
+pub struct Pending(());
+
+impl Pending {
+    pub fn begin() -> Self {
+        if let Ok(mut pending) = PENDING.lock() {
+            *pending += 1;
+        }
+        Pending(())
+    }
+}
+
+impl Drop for Pending {
+    fn drop(&mut self) {
+        if let Ok(mut pending) = PENDING.lock() {
+            *pending = pending.saturating_sub(1);
+        }
+        PENDING_DONE.notify_all();
+    }
+}
+
+/// Wait up to `timeout` for background refreshes and other `Pending` work to
+/// finish, so that a short-lived process leaves fresh data for the next one
+pub fn wait_for_revalidation(timeout: Duration) {
+    let Ok(pending) = PENDING.lock() else {
+        return;
+    };
+    let _ = PENDING_DONE.wait_timeout_while(pending, timeout, |pending| *pending > 0);
+}
+
+fn revalidate<T, E, F>(path: PathBuf, ttl: Duration, max_age: Duration, fetch: F)
+where
+    T: Serialize + DeserializeOwned + Send + 'static,
+    F: FnOnce() -> Result<T, E> + Send + 'static,
+{
+    let started = IN_FLIGHT
+        .lock()
+        .map(|mut in_flight| in_flight.insert(path.clone()))
+        .unwrap_or(false);
+    if !started {
+        return;
+    }
+    let pending = Pending::begin();
+
+    thread::spawn(move || {
+        // 其他进程正在刷新时不等待，旧值已经返回
+        let _ = fetch_locked(&path, ttl, max_age, Duration::ZERO, fetch);
+        if let Ok(mut in_flight) = IN_FLIGHT.lock() {
+            in_flight.remove(&path);
+        }
+        drop(pending);
+    });
+}
+
+/// Fetch and store while holding the entry's lock file. When another
+/// process holds it, wait up to `wait` and use what that process stored;
+/// if it is still busy after that, fall back to the cached value unless it
+/// is older than `max_age`.
+fn fetch_locked<T: Serialize + DeserializeOwned, E>(
+    path: &Path,
+    ttl: Duration,
+    max_age: Duration,
+    wait: Duration,
+    fetch: impl FnOnce() -> Result<T, E>,
+) -> Result<T, FetchError<E>> {
+    let started = now_millis();
+    let deadline = Instant::now() + wait;
+    let _lock = loop {
+        match try_lock(path) {
+            Ok(Some(lock)) => break Some(lock),
+            Ok(None) if Instant::now() < deadline => thread::sleep(LOCK_POLL),
+            Ok(None) => {
+                return read_entry(path)
+                    .filter(|entry: &Entry<T>| entry.age() < max_age)
+                    .map(|entry| entry.value)
+                    .ok_or(FetchError::Busy)
+            }
+            // 锁文件无法创建（如目录不可写）时不做协调
+            Err(_) => break None,
+        }
+    };
+
+    // 等锁期间另一个进程可能已经写回了新值
+    if let Some(entry) = read_entry::<T>(path) {
+        if entry.stored_at >= started || (!is_refreshing() && entry.age() < ttl) {
+            return Ok(entry.value);
+        }
+    }
+    let value = fetch().map_err(FetchError::Failed)?;
+    write_entry(path, &value);
+    Ok(value)
+}
+
+/// Exclusive lock on `<entry>.lock`, released when the file is dropped;
+/// `None` while another process holds it
+fn try_lock(path: &Path) -> std::io::Result<Option<fs::File>> {
+    if let Some(dir) = path.parent() {
+        create_private_dir(dir)?;
+    }
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_extension("lock"))?;
+    match file.try_lock() {
+        Ok(()) => Ok(Some(file)),
+        Err(TryLockError::WouldBlock) => Ok(None),
+        Err(TryLockError::Error(err)) => Err(err),
+    }
+}
+
+fn read_entry<T: DeserializeOwned>(path: &Path) -> Option<Entry<T>> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+// 先写临时文件再改名，其他进程不会读到写了一半的内容
+fn write_entry<T: Serialize>(path: &Path, value: &T) {
+    let Some(dir) = path.parent() else {
+        return;
+    };
+    if create_private_dir(dir).is_err() {
+        return;
+    }
+    let entry = Entry {
+        stored_at: now_millis(),
+        value,
+    };
+    let Ok(content) = serde_json::to_string(&entry) else {
+        return;
+    };
+    let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+    if write_private(&tmp, &content).is_ok() && fs::rename(&tmp, path).is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+}
+
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dir)
+}
+
+// 缓存内容包含消费数据，只允许当前用户读取
+#[cfg(unix)]
+fn write_private(path: &Path, content: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(content.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, content: &str) -> std::io::Result<()> {
+    fs::write(path, content)
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// FNV-1a：跨版本稳定的文件名哈希
+pub(crate) fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry_path(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("ccline-cache-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("quota.json")
+    }
+
+    // 写入 `age` 之前存储的条目
+    fn write_aged(path: &Path, value: u32, age: Duration) {
+        create_private_dir(path.parent().unwrap()).unwrap();
+        let entry = Entry {
+            stored_at: now_millis() - age.as_millis() as u64,
+            value,
+        };
+        fs::write(path, serde_json::to_string(&entry).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn busy_lock_falls_back_to_entry_within_max_age() {
+        let path = entry_path("within");
+        write_aged(&path, 1, Duration::from_secs(90));
+        let _held = try_lock(&path).unwrap().unwrap();
+
+        let value = fetch_locked(
+            &path,
+            Duration::from_secs(60),
+            Duration::from_secs(120),
+            Duration::ZERO,
+            || Ok::<_, ()>(2),
+        );
+
+        assert_eq!(value.ok(), Some(1));
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn busy_lock_ignores_entry_past_max_age() {
+        let path = entry_path("past");
+        write_aged(&path, 1, Duration::from_secs(300));
+        let _held = try_lock(&path).unwrap().unwrap();
+
+        let value = fetch_locked(
+            &path,
+            Duration::from_secs(60),
+            Duration::from_secs(120),
+            Duration::ZERO,
+            || Ok::<_, ()>(2),
+        );
+
+        assert!(matches!(value, Err(FetchError::Busy)));
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn free_lock_fetches_and_stores() {
+        let path = entry_path("free");
+        write_aged(&path, 1, Duration::from_secs(300));
+
+        let value = fetch_locked(
+            &path,
+            Duration::from_secs(60),
+            Duration::from_secs(120),
+            Duration::ZERO,
+            || Ok::<_, ()>(2),
+        );
+
+        assert_eq!(value.ok(), Some(2));
+        assert_eq!(read_entry::<u32>(&path).map(|entry| entry.value), Some(2));
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn failed_fetch_keeps_error_and_cached_entry() {
+        let path = entry_path("failed");
+        write_aged(&path, 1, Duration::from_secs(300));
+
+        let value = fetch_locked(
+            &path,
+            Duration::from_secs(60),
+            Duration::from_secs(120),
+            Duration::ZERO,
+            || Err::<u32, _>("timeout"),
+        );
+
+        assert!(matches!(value, Err(FetchError::Failed("timeout"))));
+        assert_eq!(read_entry::<u32>(&path).map(|entry| entry.value), Some(1));
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,76 @@
+// 共享的 HTTP 客户端：所有段落复用同一连接池与超时设置
+use reqwest::blocking::{Client, RequestBuilder};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+// 单次请求的上限；渲染时限在此之上另行生效
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+
+static CLIENT: OnceLock<Client> = OnceLock::new();
+
+/// Client shared by every segment, built on first use
+pub fn client() -> &'static Client {
+    CLIENT.get_or_init(|| {
+        Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .connect_timeout(CONNECT_TIMEOUT)
+            .user_agent(concat!("ccometixline/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_else(|_| Client::new())
+    })
+}
+
+#[derive(Debug)]
+pub enum HttpError {
+    /// Connection failure or timeout
+    Transport(reqwest::Error),
+    /// Non-success status, e.g. 401 for an expired token
+    Status(StatusCode),
+    /// Body that is not the expected JSON
+    Decode(reqwest::Error),
+    /// JSON without a usable value at the given pointer
+    Field(String),
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpError::Transport(err) => write!(f, "request failed: {}", err),
+            HttpError::Status(status) => write!(f, "server returned {}", status),
+            HttpError::Decode(err) => write!(f, "unexpected response: {}", err),
+            HttpError::Field(pointer) => write!(f, "response has no number at {}", pointer),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HttpError::Transport(err) | HttpError::Decode(err) => Some(err),
+            HttpError::Status(_) | HttpError::Field(_) => None,
+        }
+    }
+}
+
+/// Send `request` and decode its JSON body
+pub fn send_json<T: DeserializeOwned>(request: RequestBuilder) -> Result<T, HttpError> {
+    let response = request
+        .header("accept", "application/json")
+        .send()
+        .map_err(HttpError::Transport)?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(HttpError::Status(status));
+    }
+    response.json().map_err(HttpError::Decode)
+}
+
+/// GET `url` with a Bearer token. The token only travels in the request
+/// header, never on a command line.
+pub fn get_json<T: DeserializeOwned>(url: &str, token: &str) -> Result<T, HttpError> {
+    send_json(client().get(url).bearer_auth(token))
+}
@@ -0,0 +1,176 @@
+// OSC 8 超链接：终端能力检测与链接地址生成
+use crate::core::terminal::TerminalEnv;
+
+/// Whether to emit OSC 8 hyperlinks. `hyperlinks` config value: "auto"
+/// (detect from the environment), "on" or "off"
+pub fn hyperlinks_enabled(value: &str, env: &TerminalEnv) -> bool {
+    match value.trim().to_lowercase().as_str() {
+        "on" | "true" | "always" => true,
+        "off" | "false" | "never" => false,
+        _ => hyperlinks_from_env(env),
+    }
+}
+
+fn hyperlinks_from_env(env: &TerminalEnv) -> bool {
+    let var = |name: &str| env.var(name);
+    let term = var("TERM").to_lowercase();
+    if term == "dumb" || !var("NO_COLOR").is_empty() {
+        return false;
+    }
+    // tmux/screen 默认不透传 OSC 8，链接会原样显示为乱码
+    if !var("TMUX").is_empty() || term.starts_with("screen") {
+        return false;
+    }
+    if !var("WT_SESSION").is_empty()
+        || !var("KITTY_WINDOW_ID").is_empty()
+        || !var("KONSOLE_VERSION").is_empty()
+    {
+        return true;
+    }
+    // VTE 0.50 (GNOME Terminal 3.26) 起支持
+    if var("VTE_VERSION")
+        .parse::<u32>()
+        .is_ok_and(|version| version >= 5000)
+    {
+        return true;
+    }
+    matches!(
+        var("TERM_PROGRAM"),
+        "iTerm.app" | "WezTerm" | "vscode" | "ghostty" | "Hyper" | "Tabby" | "rio"
+    ) || ["kitty", "alacritty", "foot", "wezterm", "ghostty"]
+        .iter()
+        .any(|name| term.contains(name))
+}
+
+/// Wrap `text` in an OSC 8 hyperlink to `url`
+pub fn hyperlink(text: &str, url: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// `file://` URL for a local path
+pub fn file_url(path: &str) -> String {
+    let mut path = path.replace('\\', "/");
+    // Windows: C:/Users -> /C:/Users
+    if !path.starts_with('/') {
+        path.insert(0, '/');
+    }
+    format!("file://{}", percent_encode(&path, "/:"))
+}
+
+/// Web page of `branch` on the host of a git remote URL. Understands
+/// `git@host:owner/repo.git`, `ssh://git@host:22/owner/repo.git` and
+/// `https://user@host/owner/repo.git`; `None` for local or unknown remotes.
+pub fn branch_url(remote: &str, branch: &str) -> Option<String> {
+    let remote = remote.trim();
+    let (host, path) = if let Some(rest) = remote
+        .strip_prefix("https://")
+        .or_else(|| remote.strip_prefix("http://"))
+        .or_else(|| remote.strip_prefix("ssh://"))
+        .or_else(|| remote.strip_prefix("git://"))
+    {
+        rest.split_once('/')?
+    } else if !remote.contains("://") {
+        // scp 风格：git@github.com:owner/repo.git
+        remote.split_once(':')?
+    } else {
+        return None;
+    };
+
+    // 去掉用户名与端口
+    let host = host.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+    let path = path.trim_matches('/').trim_end_matches(".git");
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+
+    let branch = percent_encode(branch, "/");
+    let page = if host.contains("gitlab") {
+        format!("-/tree/{}", branch)
+    } else if host.contains("bitbucket") {
+        format!("src/{}", branch)
+    } else {
+        // GitHub、Gitea、Codeberg 等
+        format!("tree/{}", branch)
+    };
+    Some(format!("https://{}/{}/{}", host, path, page))
+}
+
+// 保留 RFC 3986 非保留字符与 `keep` 中的字符，其余按 UTF-8 字节编码
+fn percent_encode(text: &str, keep: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() || "-._~".contains(c) || keep.contains(c) {
+            output.push(c);
+        } else {
+            let mut buf = [0u8; 4];
+            for byte in c.encode_utf8(&mut buf).bytes() {
+                output.push_str(&format!("%{:02X}", byte));
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn branch_url_from_remote_forms() {
+        let expected = Some("https://github.com/owner/repo/tree/main".to_string());
+        assert_eq!(
+            branch_url("git@github.com:owner/repo.git", "main"),
+            expected
+        );
+        assert_eq!(
+            branch_url("https://github.com/owner/repo.git", "main"),
+            expected
+        );
+        assert_eq!(
+            branch_url("https://user@github.com/owner/repo/", "main"),
+            expected
+        );
+        assert_eq!(
+            branch_url("ssh://git@github.com:22/owner/repo.git", "main"),
+            expected
+        );
+    }
+
+    #[test]
+    fn branch_url_per_host_and_encoding() {
+        assert_eq!(
+            branch_url("git@gitlab.com:group/sub/repo.git", "feat/x y").as_deref(),
+            Some("https://gitlab.com/group/sub/repo/-/tree/feat/x%20y")
+        );
+        assert_eq!(
+            branch_url("https://bitbucket.org/team/repo.git", "dev").as_deref(),
+            Some("https://bitbucket.org/team/repo/src/dev")
+        );
+    }
+
+    #[test]
+    fn branch_url_rejects_local_remotes() {
+        assert_eq!(branch_url("/srv/git/repo.git", "main"), None);
+        assert_eq!(branch_url("file:///srv/git/repo.git", "main"), None);
+        assert_eq!(branch_url("git@github.com:", "main"), None);
+    }
+
+    #[test]
+    fn file_url_encodes_paths() {
+        assert_eq!(
+            file_url("/home/me/my project"),
+            "file:///home/me/my%20project"
+        );
+        assert_eq!(file_url("/tmp/项目"), "file:///tmp/%E9%A1%B9%E7%9B%AE");
+        assert_eq!(file_url(r"C:\Users\me"), "file:///C:/Users/me");
+    }
+
+    #[test]
+    fn hyperlink_wraps_text() {
+        assert_eq!(
+            hyperlink("main", "https://example.com"),
+            "\x1b]8;;https://example.com\x1b\\main\x1b]8;;\x1b\\"
+        );
+    }
+}
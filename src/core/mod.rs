@@ -1,4 +1,15 @@
+pub mod bar;
+pub mod cache;
+pub mod http;
+pub mod link;
+pub mod output;
+pub mod providers;
 pub mod segments;
 pub mod statusline;
+pub mod style;
+pub mod template;
+pub mod terminal;
+pub mod theme;
+pub mod width;
 
 pub use statusline::StatusLineGenerator;
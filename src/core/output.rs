@@ -0,0 +1,388 @@
+// 输出格式：终端 ANSI、纯文本、tmux 状态栏标记，以及 JSON / waybar / i3bar 结构化输出
+use crate::core::segments::{SegmentData, SegmentId, SegmentState};
+use crate::core::style::{Color, Style};
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Colored text for Claude Code and terminals
+    Ansi,
+    /// Text without any escape sequences
+    Plain,
+    /// Array of segments with name, text, state and colors
+    Json,
+    /// `#[fg=...]` markup for tmux `status-right`
+    Tmux,
+    /// Waybar custom module (`return-type: json`)
+    Waybar,
+    /// One update of the i3bar protocol (an array of blocks)
+    I3bar,
+}
+
+impl OutputFormat {
+    pub const NAMES: [&'static str; 6] = ["ansi", "plain", "json", "tmux", "waybar", "i3bar"];
+
+    pub fn from_name(name: &str) -> Option<OutputFormat> {
+        let format = match name.trim().to_lowercase().as_str() {
+            "ansi" => OutputFormat::Ansi,
+            "plain" | "text" => OutputFormat::Plain,
+            "json" => OutputFormat::Json,
+            "tmux" => OutputFormat::Tmux,
+            "waybar" => OutputFormat::Waybar,
+            "i3bar" | "swaybar" => OutputFormat::I3bar,
+            _ => return None,
+        };
+        Some(format)
+    }
+
+    /// Formats built from segment data instead of a rendered text line
+    pub fn is_structured(&self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Json | OutputFormat::Waybar | OutputFormat::I3bar
+        )
+    }
+}
+
+/// A visible segment with the style the theme gives it
+pub struct RenderedSegment {
+    /// Index of the `[[layout.rows]]` entry it belongs to
+    pub row: usize,
+    pub id: SegmentId,
+    pub data: SegmentData,
+    pub style: Style,
+}
+
+/// tmux status markup for `text` in `style`; `#` is escaped as `##`
+pub fn tmux_markup(style: &Style, text: &str) -> String {
+    let text = text.replace('#', "##");
+    let mut attrs = Vec::new();
+    if let Some(fg) = style.fg {
+        attrs.push(format!("fg={}", tmux_color(fg)));
+    }
+    if let Some(bg) = style.bg {
+        attrs.push(format!("bg={}", tmux_color(bg)));
+    }
+    if style.bold {
+        attrs.push("bold".to_string());
+    }
+    if attrs.is_empty() || text.is_empty() {
+        return text;
+    }
+    format!("#[{}]{}#[default]", attrs.join(","), text)
+}
+
+fn tmux_color(color: Color) -> String {
+    match color {
+        Color::Fixed(index) => format!("colour{}", index),
+        Color::Rgb(..) => color.hex(),
+        Color::Black => "black".to_string(),
+        Color::Red => "red".to_string(),
+        Color::Green => "green".to_string(),
+        Color::Yellow => "yellow".to_string(),
+        Color::Blue => "blue".to_string(),
+        Color::Magenta => "magenta".to_string(),
+        Color::Cyan => "cyan".to_string(),
+        Color::White => "white".to_string(),
+        Color::BrightBlack => "brightblack".to_string(),
+        Color::BrightRed => "brightred".to_string(),
+        Color::BrightGreen => "brightgreen".to_string(),
+        Color::BrightYellow => "brightyellow".to_string(),
+        Color::BrightBlue => "brightblue".to_string(),
+        Color::BrightMagenta => "brightmagenta".to_string(),
+        Color::BrightCyan => "brightcyan".to_string(),
+        Color::BrightWhite => "brightwhite".to_string(),
+    }
+}
+
+pub fn to_json(segments: &[RenderedSegment]) -> String {
+    let items: Vec<Value> = segments
+        .iter()
+        .map(|segment| {
+            let data = &segment.data;
+            json!({
+                "name": segment.id.name(),
+                "text": data.text,
+                "icon": data.icon,
+                "state": data.state,
+                "stale": data.stale,
+                "row": segment.row,
+                "priority": data.priority,
+                "fg": segment.style.fg.map(|c| c.hex()),
+                "bg": segment.style.bg.map(|c| c.hex()),
+                "bold": segment.style.bold,
+                "link": data.link,
+                "fields": data.fields,
+            })
+        })
+        .collect();
+    Value::Array(items).to_string()
+}
+
+/// Waybar custom module output: Pango-colored text, one tooltip line per
+/// segment and the most severe segment state as CSS class
+pub fn to_waybar(segments: &[RenderedSegment], separator: &str) -> String {
+    let text: Vec<String> = segments
+        .iter()
+        .map(|segment| {
+            let content = pango_escape(&segment.data.content());
+            let mut attrs = Vec::new();
+            if let Some(fg) = segment.style.fg {
+                attrs.push(format!("foreground=\"{}\"", fg.hex()));
+            }
+            if let Some(bg) = segment.style.bg {
+                attrs.push(format!("background=\"{}\"", bg.hex()));
+            }
+            if segment.style.bold {
+                attrs.push("weight=\"bold\"".to_string());
+            }
+            if attrs.is_empty() {
+                content
+            } else {
+                format!("<span {}>{}</span>", attrs.join(" "), content)
+            }
+        })
+        .collect();
+
+    let tooltip: Vec<String> = segments
+        .iter()
+        .map(|segment| format!("{}: {}", segment.id.name(), segment.data.content()))
+        .collect();
+
+    let state = worst_state(segments);
+    json!({
+        "text": text.join(&pango_escape(separator)),
+        "tooltip": tooltip.join("\n"),
+        "class": state,
+        "alt": state,
+    })
+    .to_string()
+}
+
+/// One i3bar status update; the `{"version":1}` header and the opening `[`
+/// of the infinite array are left to the loop that calls us
+pub fn to_i3bar(segments: &[RenderedSegment]) -> String {
+    let blocks: Vec<Value> = segments
+        .iter()
+        .map(|segment| {
+            let mut block = json!({
+                "name": segment.id.name(),
+                "full_text": segment.data.content(),
+                "short_text": segment.data.text,
+                "urgent": segment.data.state == SegmentState::Critical,
+                "separator": true,
+            });
+            if let Some(fg) = segment.style.fg {
+                block["color"] = json!(fg.hex());
+            }
+            if let Some(bg) = segment.style.bg {
+                block["background"] = json!(bg.hex());
+            }
+            block
+        })
+        .collect();
+    Value::Array(blocks).to_string()
+}
+
+fn worst_state(segments: &[RenderedSegment]) -> SegmentState {
+    segments
+        .iter()
+        .map(|segment| segment.data.state)
+        .max()
+        .unwrap_or_default()
+}
+
+fn pango_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style(fg: Option<Color>, bg: Option<Color>, bold: bool) -> Style {
+        Style { fg, bg, bold }
+    }
+
+    fn segment(id: SegmentId, data: SegmentData, style: Style) -> RenderedSegment {
+        RenderedSegment {
+            row: 0,
+            id,
+            data,
+            style,
+        }
+    }
+
+    fn sample() -> Vec<RenderedSegment> {
+        vec![
+            segment(
+                SegmentId::Model,
+                SegmentData::new("Opus").with_icon("🤖"),
+                style(Some(Color::Rgb(0x12, 0x34, 0x56)), None, true),
+            ),
+            segment(
+                SegmentId::Quota,
+                SegmentData::new("Today: $2.50")
+                    .with_state(SegmentState::Warning)
+                    .with_field("spent", 2.5)
+                    .with_link("https://example.com"),
+                style(None, Some(Color::Fixed(236)), false),
+            ),
+        ]
+    }
+
+    #[test]
+    fn from_name_accepts_aliases_and_rejects_unknown() {
+        assert_eq!(
+            OutputFormat::from_name(" Plain "),
+            Some(OutputFormat::Plain)
+        );
+        assert_eq!(OutputFormat::from_name("text"), Some(OutputFormat::Plain));
+        assert_eq!(
+            OutputFormat::from_name("swaybar"),
+            Some(OutputFormat::I3bar)
+        );
+        for name in OutputFormat::NAMES {
+            assert!(OutputFormat::from_name(name).is_some(), "{}", name);
+        }
+        assert_eq!(OutputFormat::from_name("jsno"), None);
+        assert_eq!(OutputFormat::from_name(""), None);
+    }
+
+    #[test]
+    fn tmux_markup_escapes_hash() {
+        let styled = style(Some(Color::Fixed(33)), Some(Color::Red), true);
+        assert_eq!(
+            tmux_markup(&styled, "PR #12"),
+            "#[fg=colour33,bg=red,bold]PR ##12#[default]"
+        );
+        let rgb = style(Some(Color::Rgb(255, 0, 128)), None, false);
+        assert_eq!(tmux_markup(&rgb, "x"), "#[fg=#ff0080]x#[default]");
+        assert_eq!(tmux_markup(&Style::new(), "#1"), "##1");
+        assert_eq!(tmux_markup(&styled, ""), "");
+    }
+
+    #[test]
+    fn json_lists_segments_with_style_and_fields() {
+        let output: Value = serde_json::from_str(&to_json(&sample())).unwrap();
+        assert_eq!(
+            output,
+            json!([
+                {
+                    "name": "model",
+                    "text": "Opus",
+                    "icon": "🤖",
+                    "state": "normal",
+                    "stale": false,
+                    "row": 0,
+                    "priority": 0,
+                    "fg": "#123456",
+                    "bg": null,
+                    "bold": true,
+                    "link": null,
+                    "fields": {},
+                },
+                {
+                    "name": "quota",
+                    "text": "Today: $2.50",
+                    "icon": "",
+                    "state": "warning",
+                    "stale": false,
+                    "row": 0,
+                    "priority": 0,
+                    "fg": null,
+                    "bg": Color::Fixed(236).hex(),
+                    "bold": false,
+                    "link": "https://example.com",
+                    "fields": { "spent": 2.5 },
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn waybar_uses_pango_spans_and_worst_state() {
+        let output: Value = serde_json::from_str(&to_waybar(&sample(), " | ")).unwrap();
+        assert_eq!(
+            output,
+            json!({
+                "text": format!(
+                    "<span foreground=\"#123456\" weight=\"bold\">🤖 Opus</span> | <span background=\"{}\">Today: $2.50</span>",
+                    Color::Fixed(236).hex()
+                ),
+                "tooltip": "model: 🤖 Opus\nquota: Today: $2.50",
+                "class": "warning",
+                "alt": "warning",
+            })
+        );
+    }
+
+    #[test]
+    fn waybar_escapes_pango_markup() {
+        let segments = vec![segment(
+            SegmentId::Directory,
+            SegmentData::new("a<b> & \"c\""),
+            Style::new(),
+        )];
+        let output: Value = serde_json::from_str(&to_waybar(&segments, " <> ")).unwrap();
+        assert_eq!(output["text"], "a&lt;b&gt; &amp; &quot;c&quot;");
+        // tooltip 是纯文本，不做转义
+        assert_eq!(output["tooltip"], "directory: a<b> & \"c\"");
+        assert_eq!(output["class"], "normal");
+    }
+
+    #[test]
+    fn waybar_class_is_most_severe_state() {
+        let states = [
+            SegmentState::Good,
+            SegmentState::Critical,
+            SegmentState::Warning,
+        ];
+        let segments: Vec<RenderedSegment> = states
+            .iter()
+            .map(|state| {
+                segment(
+                    SegmentId::Git,
+                    SegmentData::new("x").with_state(*state),
+                    Style::new(),
+                )
+            })
+            .collect();
+        assert_eq!(worst_state(&segments), SegmentState::Critical);
+        assert_eq!(worst_state(&[]), SegmentState::Normal);
+
+        let output: Value = serde_json::from_str(&to_waybar(&segments, " ")).unwrap();
+        assert_eq!(output["class"], "critical");
+    }
+
+    #[test]
+    fn i3bar_blocks_mark_critical_as_urgent() {
+        let mut segments = sample();
+        segments[1].data.state = SegmentState::Critical;
+        let output: Value = serde_json::from_str(&to_i3bar(&segments)).unwrap();
+        assert_eq!(
+            output,
+            json!([
+                {
+                    "name": "model",
+                    "full_text": "🤖 Opus",
+                    "short_text": "Opus",
+                    "urgent": false,
+                    "separator": true,
+                    "color": "#123456",
+                },
+                {
+                    "name": "quota",
+                    "full_text": "Today: $2.50",
+                    "short_text": "Today: $2.50",
+                    "urgent": true,
+                    "separator": true,
+                    "background": Color::Fixed(236).hex(),
+                },
+            ])
+        );
+    }
+}
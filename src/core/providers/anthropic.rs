@@ -0,0 +1,276 @@
+// Anthropic Admin API：按天汇总组织（或单个 workspace）的 cost report 与 usage report
+use super::{deserialize_string_to_f64, QuotaProvider, QuotaUsage};
+use crate::config::AnthropicQuotaConfig;
+use crate::core::http::{self, HttpError};
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use serde::Deserialize;
+
+const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+const COST_REPORT_PATH: &str = "/v1/organizations/cost_report";
+const USAGE_REPORT_PATH: &str = "/v1/organizations/usage_report/messages";
+/// `workspace_id` selecting the Default workspace, reported as `null`
+pub const DEFAULT_WORKSPACE: &str = "default";
+pub const ADMIN_KEY_ENV: &str = "ANTHROPIC_ADMIN_KEY";
+// 一个月最多 31 个按天的桶；分页只是兜底
+const MAX_PAGES: usize = 4;
+
+#[derive(Debug, Deserialize)]
+struct CostReport {
+    data: Vec<CostBucket>,
+    #[serde(default)]
+    has_more: bool,
+    next_page: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CostBucket {
+    /// RFC 3339, midnight UTC
+    starting_at: String,
+    results: Vec<CostResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CostResult {
+    /// Cents, as a decimal string
+    #[serde(deserialize_with = "deserialize_string_to_f64")]
+    amount: f64,
+    workspace_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UsageReport {
+    data: Vec<UsageBucket>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UsageBucket {
+    results: Vec<UsageResult>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct UsageResult {
+    uncached_input_tokens: u64,
+    cache_read_input_tokens: u64,
+    cache_creation: CacheCreation,
+    output_tokens: u64,
+    workspace_id: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct CacheCreation {
+    ephemeral_1h_input_tokens: u64,
+    ephemeral_5m_input_tokens: u64,
+}
+
+impl UsageResult {
+    fn tokens(&self) -> u64 {
+        self.uncached_input_tokens
+            + self.cache_read_input_tokens
+            + self.cache_creation.ephemeral_1h_input_tokens
+            + self.cache_creation.ephemeral_5m_input_tokens
+            + self.output_tokens
+    }
+}
+
+pub struct AnthropicProvider {
+    config: AnthropicQuotaConfig,
+    url: String,
+    usage_url: String,
+}
+
+impl AnthropicProvider {
+    pub fn new(config: &AnthropicQuotaConfig) -> Self {
+        let base_url = if config.base_url.is_empty() {
+            DEFAULT_BASE_URL
+        } else {
+            config.base_url.trim_end_matches('/')
+        };
+        Self {
+            url: format!("{}{}", base_url, COST_REPORT_PATH),
+            usage_url: format!("{}{}", base_url, USAGE_REPORT_PATH),
+            config: config.clone(),
+        }
+    }
+
+    /// Whether a result grouped by workspace counts: every workspace when
+    /// `workspace_id` is empty, `null` results for "default"
+    fn counts_workspace(&self, workspace_id: Option<&str>) -> bool {
+        match self.config.workspace_id.trim() {
+            "" => true,
+            id if id.eq_ignore_ascii_case(DEFAULT_WORKSPACE) => workspace_id.is_none(),
+            id => workspace_id == Some(id),
+        }
+    }
+
+    fn report_query(
+        &self,
+        starting_at: NaiveDate,
+        today: NaiveDate,
+    ) -> Vec<(&'static str, String)> {
+        let mut query = vec![
+            ("starting_at", format!("{}T00:00:00Z", starting_at)),
+            (
+                "ending_at",
+                format!("{}T00:00:00Z", today + Duration::days(1)),
+            ),
+            ("bucket_width", "1d".to_string()),
+            ("limit", "31".to_string()),
+        ];
+        if !self.config.workspace_id.trim().is_empty() {
+            query.push(("group_by[]", "workspace_id".to_string()));
+        }
+        query
+    }
+
+    fn request(&self, url: &str, admin_key: &str) -> reqwest::blocking::RequestBuilder {
+        http::client()
+            .get(url)
+            .header("x-api-key", admin_key)
+            .header("anthropic-version", "2023-06-01")
+    }
+
+    /// Cost per day (UTC) from the first of this month to today, in USD
+    fn daily_costs(
+        &self,
+        admin_key: &str,
+        today: NaiveDate,
+    ) -> Result<Vec<(String, f64)>, HttpError> {
+        let month_start = today.with_day(1).unwrap_or(today);
+        let query = self.report_query(month_start, today);
+
+        let mut costs = Vec::new();
+        let mut page = None;
+        for _ in 0..MAX_PAGES {
+            let mut request = self.request(&self.url, admin_key).query(&query);
+            if let Some(page) = &page {
+                request = request.query(&[("page", page)]);
+            }
+            let report: CostReport = http::send_json(request)?;
+
+            for bucket in report.data {
+                let cents: f64 = bucket
+                    .results
+                    .iter()
+                    .filter(|result| self.counts_workspace(result.workspace_id.as_deref()))
+                    .map(|result| result.amount)
+                    .sum();
+                costs.push((bucket.starting_at, cents / 100.0));
+            }
+            match report.next_page {
+                Some(next) if report.has_more => page = Some(next),
+                _ => break,
+            }
+        }
+        Ok(costs)
+    }
+
+    /// Input, cache and output tokens used today (UTC)
+    fn tokens_today(&self, admin_key: &str, today: NaiveDate) -> Result<u64, HttpError> {
+        let request = self
+            .request(&self.usage_url, admin_key)
+            .query(&self.report_query(today, today));
+        let report: UsageReport = http::send_json(request)?;
+        Ok(report
+            .data
+            .iter()
+            .flat_map(|bucket| &bucket.results)
+            .filter(|result| self.counts_workspace(result.workspace_id.as_deref()))
+            .map(UsageResult::tokens)
+            .sum())
+    }
+}
+
+impl QuotaProvider for AnthropicProvider {
+    fn name(&self) -> &'static str {
+        "anthropic"
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.url
+    }
+
+    // Admin API 需要组织管理员密钥，普通 API key 无权访问
+    fn credential(&self, _api_key: Option<&str>) -> Option<String> {
+        Some(self.config.admin_key.clone())
+            .filter(|key| !key.is_empty())
+            .or_else(|| std::env::var(ADMIN_KEY_ENV).ok())
+            .filter(|key| !key.trim().is_empty())
+    }
+
+    fn fetch(&self, admin_key: &str) -> Result<QuotaUsage, HttpError> {
+        let today = Utc::now().date_naive();
+        let costs = self.daily_costs(admin_key, today)?;
+
+        let today_prefix = today.to_string();
+        let spent: f64 = costs
+            .iter()
+            .filter(|(day, _)| day.starts_with(&today_prefix))
+            .map(|(_, cost)| cost)
+            .sum();
+        let monthly_spent: f64 = costs.iter().map(|(_, cost)| cost).sum();
+        let budget = self.config.daily_budget.max(0.0);
+
+        Ok(QuotaUsage {
+            spent,
+            budget,
+            remaining: (budget - spent).max(0.0),
+            monthly_spent: Some(monthly_spent),
+            monthly_budget: Some(self.config.monthly_budget).filter(|budget| *budget > 0.0),
+            // token 用量只是附加信息，usage report 失败时不影响花费
+            tokens: self.tokens_today(admin_key, today).ok(),
+            utc: true,
+            ..QuotaUsage::default()
+        })
+    }
+
+    fn dashboard_url(&self) -> Option<&'static str> {
+        Some("https://console.anthropic.com/settings/cost")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(workspace_id: &str) -> AnthropicProvider {
+        AnthropicProvider::new(&AnthropicQuotaConfig {
+            workspace_id: workspace_id.to_string(),
+            ..AnthropicQuotaConfig::default()
+        })
+    }
+
+    #[test]
+    fn workspace_selection() {
+        assert!(provider("").counts_workspace(None));
+        assert!(provider("").counts_workspace(Some("wrkspc_a")));
+        assert!(provider("wrkspc_a").counts_workspace(Some("wrkspc_a")));
+        assert!(!provider("wrkspc_a").counts_workspace(Some("wrkspc_b")));
+        assert!(!provider("wrkspc_a").counts_workspace(None));
+        assert!(provider("default").counts_workspace(None));
+        assert!(!provider("Default").counts_workspace(Some("wrkspc_a")));
+    }
+
+    #[test]
+    fn usage_tokens_include_cache() {
+        let result: UsageResult = serde_json::from_str(
+            r#"{"uncached_input_tokens":10,"cache_creation":{"ephemeral_1h_input_tokens":1,"ephemeral_5m_input_tokens":2},"cache_read_input_tokens":30,"output_tokens":4,"server_tool_use":{"web_search_requests":0}}"#,
+        )
+        .unwrap();
+        assert_eq!(result.tokens(), 47);
+        assert_eq!(result.workspace_id, None);
+    }
+
+    #[test]
+    fn report_query_groups_only_with_a_workspace() {
+        let today = NaiveDate::from_ymd_opt(2026, 2, 28).unwrap();
+        let query = provider("").report_query(today.with_day(1).unwrap(), today);
+        assert!(query.contains(&("starting_at", "2026-02-01T00:00:00Z".to_string())));
+        assert!(query.contains(&("ending_at", "2026-03-01T00:00:00Z".to_string())));
+        assert!(!query.iter().any(|(name, _)| *name == "group_by[]"));
+        assert!(provider("default")
+            .report_query(today, today)
+            .contains(&("group_by[]", "workspace_id".to_string())));
+    }
+}
@@ -0,0 +1,184 @@
+// 自定义余额接口：地址、请求方法、认证头与 JSON pointer 字段映射均来自 [quota.custom]
+use super::{number_at, QuotaProvider, QuotaUsage};
+use crate::config::CustomQuotaConfig;
+use crate::core::http::{self, HttpError};
+use reqwest::Method;
+use serde_json::Value;
+
+pub struct CustomProvider {
+    config: CustomQuotaConfig,
+}
+
+impl CustomProvider {
+    pub fn new(config: &CustomQuotaConfig) -> Self {
+        Self {
+            config: config.clone(),
+        }
+    }
+
+    /// Header name and value with `{api_key}` filled in
+    fn auth_header<'a>(&'a self, api_key: &str) -> Option<(&'a str, String)> {
+        let (name, value) = self.config.auth_header.split_once(':')?;
+        Some((name.trim(), value.trim().replace("{api_key}", api_key)))
+    }
+
+    /// Mapped amount; `Ok(None)` when the mapping is not configured
+    fn amount(&self, body: &Value, pointer: &str) -> Result<Option<f64>, HttpError> {
+        if pointer.is_empty() {
+            return Ok(None);
+        }
+        number_at(body, pointer)
+            .map(Some)
+            .ok_or_else(|| HttpError::Field(pointer.to_string()))
+    }
+
+    fn currency(&self, body: &Value) -> Option<String> {
+        let currency = self.config.currency.trim();
+        if !currency.starts_with('/') {
+            return Some(currency.to_string()).filter(|code| !code.is_empty());
+        }
+        body.pointer(currency)?.as_str().map(str::to_string)
+    }
+}
+
+impl QuotaProvider for CustomProvider {
+    fn name(&self) -> &'static str {
+        "custom"
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.config.url
+    }
+
+    // 认证头不引用 {api_key}（为空或写死了 token）时，没有 API key 也照常请求
+    fn credential(&self, api_key: Option<&str>) -> Option<String> {
+        if !self.config.auth_header.contains("{api_key}") {
+            return Some(String::new());
+        }
+        api_key.map(str::to_string)
+    }
+
+    // 写死在认证头里的 token 不经过 credential，按渲染后的整个头区分缓存
+    fn cache_key(&self, credential: &str) -> String {
+        self.auth_header(credential)
+            .map(|(name, value)| format!("{}: {}", name, value))
+            .unwrap_or_default()
+    }
+
+    fn fetch(&self, api_key: &str) -> Result<QuotaUsage, HttpError> {
+        let method = Method::from_bytes(self.config.method.trim().to_uppercase().as_bytes())
+            .unwrap_or(Method::GET);
+        let mut request = http::client().request(method, &self.config.url);
+        if let Some((name, value)) = self.auth_header(api_key) {
+            request = request.header(name, value);
+        }
+        let body: Value = http::send_json(request)?;
+
+        // 三个金额任意配置两个即可推出第三个；只有余额时花费与预算记为 0
+        let spent = self.amount(&body, &self.config.spent)?;
+        let budget = self.amount(&body, &self.config.budget)?;
+        let remaining = self.amount(&body, &self.config.remaining)?;
+        let (spent, budget, remaining) = complete_amounts(spent, budget, remaining)
+            .ok_or_else(|| HttpError::Field(self.config.spent.clone()))?;
+
+        Ok(QuotaUsage {
+            spent,
+            budget,
+            remaining,
+            currency: self.currency(&body),
+            ..QuotaUsage::default()
+        })
+    }
+}
+
+/// Spent, budget and remaining from whichever of them are mapped
+fn complete_amounts(
+    spent: Option<f64>,
+    budget: Option<f64>,
+    remaining: Option<f64>,
+) -> Option<(f64, f64, f64)> {
+    let amounts = match (spent, budget, remaining) {
+        (Some(spent), Some(budget), remaining) => {
+            (spent, budget, remaining.unwrap_or(budget - spent))
+        }
+        (Some(spent), None, Some(remaining)) => (spent, spent + remaining, remaining),
+        (None, Some(budget), Some(remaining)) => (budget - remaining, budget, remaining),
+        (Some(spent), None, None) => (spent, 0.0, 0.0),
+        (None, None, Some(remaining)) => (0.0, 0.0, remaining),
+        _ => return None,
+    };
+    Some(amounts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(auth_header: &str) -> CustomProvider {
+        CustomProvider::new(&CustomQuotaConfig {
+            auth_header: auth_header.to_string(),
+            ..CustomQuotaConfig::default()
+        })
+    }
+
+    #[test]
+    fn credential_without_api_key_placeholder() {
+        assert_eq!(provider("").credential(None), Some(String::new()));
+        assert_eq!(
+            provider("X-Token: fixed").credential(None),
+            Some(String::new())
+        );
+        assert_eq!(
+            provider("Authorization: Bearer {api_key}").credential(None),
+            None
+        );
+        assert_eq!(
+            provider("Authorization: Bearer {api_key}").credential(Some("key")),
+            Some("key".to_string())
+        );
+    }
+
+    #[test]
+    fn cache_key_includes_hardcoded_token() {
+        assert_eq!(provider("X-Token: one").cache_key(""), "X-Token: one");
+        assert_ne!(
+            provider("X-Token: one").cache_key(""),
+            provider("X-Token: two").cache_key("")
+        );
+        assert_eq!(
+            provider("Authorization: Bearer {api_key}").cache_key("key"),
+            "Authorization: Bearer key"
+        );
+        assert_eq!(provider("").cache_key(""), "");
+    }
+
+    #[test]
+    fn any_two_amounts_give_the_third() {
+        assert_eq!(
+            complete_amounts(Some(3.0), Some(10.0), None),
+            Some((3.0, 10.0, 7.0))
+        );
+        assert_eq!(
+            complete_amounts(Some(3.0), None, Some(7.0)),
+            Some((3.0, 10.0, 7.0))
+        );
+        assert_eq!(
+            complete_amounts(None, Some(10.0), Some(7.0)),
+            Some((3.0, 10.0, 7.0))
+        );
+    }
+
+    #[test]
+    fn single_amounts() {
+        assert_eq!(
+            complete_amounts(Some(3.0), None, None),
+            Some((3.0, 0.0, 0.0))
+        );
+        assert_eq!(
+            complete_amounts(None, None, Some(7.0)),
+            Some((0.0, 0.0, 7.0))
+        );
+        assert_eq!(complete_amounts(None, Some(10.0), None), None);
+        assert_eq!(complete_amounts(None, None, None), None);
+    }
+}
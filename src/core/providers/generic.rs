@@ -0,0 +1,48 @@
+// 其他中转站：/v1/dashboard/usage 额度接口，认证方式不确定时两种请求头都带上
+use super::{QuotaProvider, QuotaUsage};
+use crate::core::http::{self, HttpError};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct DashboardUsageResponse {
+    #[serde(rename = "remaining_credit_in_usd")]
+    remaining: f64,
+    #[serde(rename = "credit_limit_in_usd")]
+    limit: f64,
+}
+
+pub struct GenericProvider {
+    url: String,
+}
+
+impl GenericProvider {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            url: format!("{}/v1/dashboard/usage", base_url.trim_end_matches('/')),
+        }
+    }
+}
+
+impl QuotaProvider for GenericProvider {
+    fn name(&self) -> &'static str {
+        "generic"
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.url
+    }
+
+    fn fetch(&self, api_key: &str) -> Result<QuotaUsage, HttpError> {
+        let request = http::client()
+            .get(&self.url)
+            .bearer_auth(api_key)
+            .header("x-api-key", api_key);
+        let usage: DashboardUsageResponse = http::send_json(request)?;
+        Ok(QuotaUsage {
+            spent: usage.limit - usage.remaining,
+            budget: usage.limit,
+            remaining: usage.remaining,
+            ..QuotaUsage::default()
+        })
+    }
+}
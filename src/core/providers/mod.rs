@@ -0,0 +1,333 @@
+// 配额数据来源：PackyCode、Anthropic、通用中转站、one-api 与自定义接口，统一换算为 QuotaUsage
+pub mod anthropic;
+pub mod custom;
+pub mod generic;
+pub mod oneapi;
+pub mod packycode;
+pub mod relay;
+
+use crate::config::{ApiSettings, PackyCodeConfig, QuotaConfig};
+use crate::core::http::HttpError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+
+pub use anthropic::AnthropicProvider;
+pub use custom::CustomProvider;
+pub use generic::GenericProvider;
+pub use oneapi::OneApiProvider;
+pub use packycode::PackyCodeProvider;
+pub use relay::RelayProvider;
+
+/// Spend and budget as the segment shows them, whatever the backend's format
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct QuotaUsage {
+    /// Spent today
+    pub spent: f64,
+    /// Daily budget; 0 when the backend has none
+    pub budget: f64,
+    pub remaining: f64,
+    pub monthly_spent: Option<f64>,
+    pub monthly_budget: Option<f64>,
+    /// Currency code or symbol; `None` is USD
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// Usage over the account's lifetime, from backends that only report a
+    /// balance; `spent` and `budget` are 0 then
+    #[serde(default)]
+    pub total_spent: Option<f64>,
+    /// No credit limit, so `remaining` means nothing
+    #[serde(default)]
+    pub unlimited: bool,
+    /// Tokens used today, from backends that report them
+    #[serde(default)]
+    pub tokens: Option<u64>,
+    /// Days and months are UTC rather than local time
+    #[serde(default)]
+    pub utc: bool,
+}
+
+impl QuotaUsage {
+    /// Symbol written before amounts
+    pub fn currency_symbol(&self) -> String {
+        match self.currency.as_deref().map(str::trim) {
+            None | Some("") => "$".to_string(),
+            Some(code) => match code.to_uppercase().as_str() {
+                "USD" => "$".to_string(),
+                "CNY" | "RMB" | "JPY" => "¥".to_string(),
+                "EUR" => "€".to_string(),
+                "GBP" => "£".to_string(),
+                _ if code.chars().count() == 1 => code.to_string(),
+                _ => format!("{} ", code),
+            },
+        }
+    }
+}
+
+/// A backend the quota segment can read spend from. Responses are cached
+/// by the segment, keyed by `name` and `endpoint`.
+pub trait QuotaProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// URL of the request
+    fn endpoint(&self) -> &str;
+    /// Key passed to `fetch`, by default the Claude Code API key; `None`
+    /// hides the segment
+    fn credential(&self, api_key: Option<&str>) -> Option<String> {
+        api_key.map(str::to_string)
+    }
+    /// What the cached response is keyed on besides the endpoint, by
+    /// default the credential
+    fn cache_key(&self, credential: &str) -> String {
+        credential.to_string()
+    }
+    fn fetch(&self, api_key: &str) -> Result<QuotaUsage, HttpError>;
+    /// Console page linked from the segment
+    fn dashboard_url(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKind {
+    PackyCode,
+    /// Admin API cost report
+    Anthropic,
+    /// Other relays serving `/v1/dashboard/usage`
+    Generic,
+    /// Relays built on one-api / new-api
+    OneApi,
+    /// `[quota.custom]` endpoint and field mapping
+    Custom,
+    /// Unknown relay in auto mode: PackyCode users/info (custom PackyCode
+    /// domains), then `/v1/dashboard/usage`
+    Relay,
+}
+
+impl ProviderKind {
+    /// `[quota] provider` value; "auto" and unknown names detect the provider
+    pub fn from_config(
+        config: &QuotaConfig,
+        settings: &ApiSettings,
+        packycode: &PackyCodeConfig,
+    ) -> Self {
+        match config.provider.trim().to_lowercase().as_str() {
+            "packycode" | "packy" => ProviderKind::PackyCode,
+            "anthropic" => ProviderKind::Anthropic,
+            "generic" => ProviderKind::Generic,
+            "oneapi" | "one-api" | "newapi" | "new-api" => ProviderKind::OneApi,
+            "custom" => ProviderKind::Custom,
+            _ => Self::detect(config, settings, packycode),
+        }
+    }
+
+    /// A `[quota.custom] url` means custom, a `[quota.oneapi] access_token`
+    /// one-api and a `[quota.anthropic] admin_key` Anthropic; otherwise
+    /// detect from `ANTHROPIC_BASE_URL`, where an `info_url` or
+    /// `[packycode] base_url` also means PackyCode. Other relays are probed
+    /// as PackyCode first, then as generic relays
+    pub fn detect(
+        config: &QuotaConfig,
+        settings: &ApiSettings,
+        packycode: &PackyCodeConfig,
+    ) -> Self {
+        if !config.custom.url.is_empty() {
+            ProviderKind::Custom
+        } else if !config.oneapi.access_token.is_empty() {
+            ProviderKind::OneApi
+        } else if !config.anthropic.admin_key.is_empty() {
+            ProviderKind::Anthropic
+        } else if settings.base_url.contains("packycode.com")
+            || settings.info_url.is_some()
+            || !packycode.base_url.is_empty()
+        {
+            ProviderKind::PackyCode
+        } else if settings.base_url.contains("api.anthropic.com") {
+            ProviderKind::Anthropic
+        } else {
+            ProviderKind::Relay
+        }
+    }
+
+    /// Whether the PackyCode ranking can apply
+    pub fn may_be_packycode(&self) -> bool {
+        matches!(self, ProviderKind::PackyCode | ProviderKind::Relay)
+    }
+
+    pub fn provider(
+        &self,
+        config: &QuotaConfig,
+        settings: &ApiSettings,
+        packycode: &PackyCodeConfig,
+    ) -> Arc<dyn QuotaProvider> {
+        match self {
+            ProviderKind::PackyCode => Arc::new(PackyCodeProvider::new(
+                &packycode.endpoints(&settings.base_url, settings.info_url.as_deref()),
+            )),
+            ProviderKind::Anthropic => Arc::new(AnthropicProvider::new(&config.anthropic)),
+            ProviderKind::Generic => Arc::new(GenericProvider::new(&settings.base_url)),
+            ProviderKind::OneApi => {
+                Arc::new(OneApiProvider::new(&config.oneapi, &settings.base_url))
+            }
+            ProviderKind::Custom => Arc::new(CustomProvider::new(&config.custom)),
+            ProviderKind::Relay => Arc::new(RelayProvider::new(
+                &packycode.endpoints(&settings.base_url, settings.info_url.as_deref()),
+                &settings.base_url,
+            )),
+        }
+    }
+}
+
+// 自定义反序列化函数，将字符串转换为 f64
+pub(crate) fn deserialize_string_to_f64<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrFloat {
+        String(String),
+        Float(f64),
+    }
+
+    match StringOrFloat::deserialize(deserializer)? {
+        StringOrFloat::String(s) => s
+            .parse::<f64>()
+            .map_err(|_| de::Error::custom("Failed to parse string as f64")),
+        StringOrFloat::Float(f) => Ok(f),
+    }
+}
+
+/// Number at `pointer` in `value`, written either as a number or a string
+pub(crate) fn number_at(value: &Value, pointer: &str) -> Option<f64> {
+    match value.pointer(pointer)? {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(base_url: &str, config: &QuotaConfig, packycode: &PackyCodeConfig) -> ProviderKind {
+        let settings = ApiSettings {
+            api_key: Some("key".to_string()),
+            base_url: base_url.to_string(),
+            info_url: None,
+        };
+        ProviderKind::from_config(config, &settings, packycode)
+    }
+
+    fn auto(base_url: &str) -> ProviderKind {
+        detect(
+            base_url,
+            &QuotaConfig::default(),
+            &PackyCodeConfig::default(),
+        )
+    }
+
+    #[test]
+    fn detects_from_base_url() {
+        assert_eq!(
+            auto("https://share-api.packycode.com"),
+            ProviderKind::PackyCode
+        );
+        assert_eq!(auto("https://api.anthropic.com"), ProviderKind::Anthropic);
+        assert_eq!(auto("https://relay.example.com"), ProviderKind::Relay);
+    }
+
+    #[test]
+    fn configured_sections_win() {
+        let mut config = QuotaConfig::default();
+        config.anthropic.admin_key = "sk-ant-admin".to_string();
+        assert_eq!(
+            detect(
+                "https://relay.example.com",
+                &config,
+                &PackyCodeConfig::default()
+            ),
+            ProviderKind::Anthropic
+        );
+        config.oneapi.access_token = "token".to_string();
+        assert_eq!(
+            detect(
+                "https://relay.example.com",
+                &config,
+                &PackyCodeConfig::default()
+            ),
+            ProviderKind::OneApi
+        );
+        config.custom.url = "https://relay.example.com/balance".to_string();
+        assert_eq!(
+            detect(
+                "https://relay.example.com",
+                &config,
+                &PackyCodeConfig::default()
+            ),
+            ProviderKind::Custom
+        );
+
+        let packycode = PackyCodeConfig {
+            base_url: "http://127.0.0.1:8080".to_string(),
+            ..PackyCodeConfig::default()
+        };
+        assert_eq!(
+            detect(
+                "https://relay.example.com",
+                &QuotaConfig::default(),
+                &packycode
+            ),
+            ProviderKind::PackyCode
+        );
+    }
+
+    #[test]
+    fn explicit_provider_and_aliases() {
+        let config = |provider: &str| QuotaConfig {
+            provider: provider.to_string(),
+            ..QuotaConfig::default()
+        };
+        let packycode = PackyCodeConfig::default();
+        let base_url = "https://api.anthropic.com";
+        assert_eq!(
+            detect(base_url, &config("packy"), &packycode),
+            ProviderKind::PackyCode
+        );
+        assert_eq!(
+            detect(base_url, &config("Generic"), &packycode),
+            ProviderKind::Generic
+        );
+        assert_eq!(
+            detect(base_url, &config("new-api"), &packycode),
+            ProviderKind::OneApi
+        );
+        assert_eq!(
+            detect(base_url, &config("unknown"), &packycode),
+            ProviderKind::Anthropic
+        );
+    }
+
+    #[test]
+    fn currency_symbols() {
+        let usage = |currency: Option<&str>| QuotaUsage {
+            currency: currency.map(str::to_string),
+            ..QuotaUsage::default()
+        };
+        assert_eq!(usage(None).currency_symbol(), "$");
+        assert_eq!(usage(Some("cny")).currency_symbol(), "¥");
+        assert_eq!(usage(Some("€")).currency_symbol(), "€");
+        assert_eq!(usage(Some("CHF")).currency_symbol(), "CHF ");
+    }
+
+    #[test]
+    fn number_at_reads_numbers_and_strings() {
+        let value: Value = serde_json::json!({"a": {"n": 1.5, "s": " 2.25 ", "x": true}});
+        assert_eq!(number_at(&value, "/a/n"), Some(1.5));
+        assert_eq!(number_at(&value, "/a/s"), Some(2.25));
+        assert_eq!(number_at(&value, "/a/x"), None);
+        assert_eq!(number_at(&value, "/missing"), None);
+    }
+}
@@ -0,0 +1,200 @@
+// one-api / new-api 中转站：OpenAI 风格的 billing 接口，或按额度单位计的 /api/user/self
+use super::{deserialize_string_to_f64, QuotaProvider, QuotaUsage};
+use crate::config::OneApiQuotaConfig;
+use crate::core::http::{self, HttpError};
+use serde::Deserialize;
+
+const SUBSCRIPTION_PATH: &str = "/dashboard/billing/subscription";
+const USAGE_PATH: &str = "/dashboard/billing/usage";
+const USER_SELF_PATH: &str = "/api/user/self";
+const DEFAULT_QUOTA_PER_UNIT: f64 = 500000.0;
+// one-api 对不限额度的令牌返回 hard_limit_usd = 100000000
+const UNLIMITED_USD: f64 = 100_000_000.0;
+
+#[derive(Debug, Deserialize)]
+struct Subscription {
+    /// Total credit: what is left plus what was used
+    #[serde(deserialize_with = "deserialize_string_to_f64")]
+    hard_limit_usd: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct BillingUsage {
+    /// Used so far, in cents
+    #[serde(deserialize_with = "deserialize_string_to_f64")]
+    total_usage: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserSelf {
+    data: UserData,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserData {
+    /// Quota units left
+    #[serde(deserialize_with = "deserialize_string_to_f64")]
+    quota: f64,
+    #[serde(deserialize_with = "deserialize_string_to_f64")]
+    used_quota: f64,
+}
+
+pub struct OneApiProvider {
+    config: OneApiQuotaConfig,
+    base_url: String,
+    url: String,
+}
+
+impl OneApiProvider {
+    /// `api_base_url` is used when `[quota.oneapi] base_url` is empty
+    pub fn new(config: &OneApiQuotaConfig, api_base_url: &str) -> Self {
+        let base_url = if config.base_url.is_empty() {
+            api_base_url
+        } else {
+            &config.base_url
+        }
+        .trim_end_matches('/')
+        .to_string();
+        let path = if config.is_user_mode() {
+            USER_SELF_PATH
+        } else {
+            SUBSCRIPTION_PATH
+        };
+        Self {
+            url: format!("{}{}", base_url, path),
+            base_url,
+            config: config.clone(),
+        }
+    }
+
+    fn fetch_billing(&self, api_key: &str) -> Result<QuotaUsage, HttpError> {
+        let subscription: Subscription = http::get_json(&self.url, api_key)?;
+        let usage: BillingUsage =
+            http::get_json(&format!("{}{}", self.base_url, USAGE_PATH), api_key)?;
+        Ok(billing_usage(&subscription, &usage))
+    }
+
+    fn fetch_user(&self, access_token: &str) -> Result<QuotaUsage, HttpError> {
+        let mut request = http::client().get(&self.url).bearer_auth(access_token);
+        if self.config.user_id != 0 {
+            request = request.header("New-Api-User", self.config.user_id.to_string());
+        }
+        let user: UserSelf = http::send_json(request)?;
+
+        let per_unit = if self.config.quota_per_unit > 0.0 {
+            self.config.quota_per_unit
+        } else {
+            DEFAULT_QUOTA_PER_UNIT
+        };
+        Ok(user_usage(&user.data, per_unit))
+    }
+}
+
+// 两种接口的用量都是账户累计值而不是今天的花费，按余额显示
+// 订阅额度为剩余加已用，用量以美分计；/dashboard/billing/usage 的日期参数会被忽略
+fn billing_usage(subscription: &Subscription, usage: &BillingUsage) -> QuotaUsage {
+    let total_spent = usage.total_usage / 100.0;
+    let unlimited = subscription.hard_limit_usd >= UNLIMITED_USD;
+    QuotaUsage {
+        remaining: if unlimited {
+            0.0
+        } else {
+            (subscription.hard_limit_usd - total_spent).max(0.0)
+        },
+        total_spent: Some(total_spent),
+        unlimited,
+        ..QuotaUsage::default()
+    }
+}
+
+fn user_usage(data: &UserData, per_unit: f64) -> QuotaUsage {
+    QuotaUsage {
+        remaining: data.quota / per_unit,
+        total_spent: Some(data.used_quota / per_unit),
+        ..QuotaUsage::default()
+    }
+}
+
+impl OneApiQuotaConfig {
+    /// `/api/user/self` with the access token instead of the billing endpoints
+    pub fn is_user_mode(&self) -> bool {
+        self.mode.trim().eq_ignore_ascii_case("user")
+    }
+}
+
+impl QuotaProvider for OneApiProvider {
+    fn name(&self) -> &'static str {
+        "oneapi"
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.url
+    }
+
+    // user 模式使用系统访问令牌，而不是 API key
+    fn credential(&self, api_key: Option<&str>) -> Option<String> {
+        if self.config.is_user_mode() {
+            Some(self.config.access_token.clone()).filter(|token| !token.is_empty())
+        } else {
+            api_key.map(str::to_string)
+        }
+    }
+
+    fn fetch(&self, api_key: &str) -> Result<QuotaUsage, HttpError> {
+        if self.config.is_user_mode() {
+            self.fetch_user(api_key)
+        } else {
+            self.fetch_billing(api_key)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn billing_is_a_balance() {
+        let usage = billing_usage(
+            &Subscription {
+                hard_limit_usd: 20.0,
+            },
+            &BillingUsage { total_usage: 250.0 },
+        );
+        assert_eq!(usage.spent, 0.0);
+        assert_eq!(usage.budget, 0.0);
+        assert_eq!(usage.remaining, 17.5);
+        assert_eq!(usage.total_spent, Some(2.5));
+        assert!(!usage.unlimited);
+    }
+
+    #[test]
+    fn unlimited_subscription_has_no_balance() {
+        let usage = billing_usage(
+            &Subscription {
+                hard_limit_usd: 100_000_000.0,
+            },
+            &BillingUsage {
+                total_usage: 1234.0,
+            },
+        );
+        assert!(usage.unlimited);
+        assert_eq!(usage.remaining, 0.0);
+        assert_eq!(usage.budget, 0.0);
+        assert_eq!(usage.total_spent, Some(12.34));
+    }
+
+    #[test]
+    fn user_quota_converts_units() {
+        let usage = user_usage(
+            &UserData {
+                quota: 8_750_000.0,
+                used_quota: 1_250_000.0,
+            },
+            DEFAULT_QUOTA_PER_UNIT,
+        );
+        assert_eq!(usage.remaining, 17.5);
+        assert_eq!(usage.total_spent, Some(2.5));
+        assert_eq!(usage.budget, 0.0);
+    }
+}
@@ -0,0 +1,65 @@
+// PackyCode users/info：按日与按月的预算和花费
+use super::{deserialize_string_to_f64, QuotaProvider, QuotaUsage};
+use crate::config::PackyCodeEndpoints;
+use crate::core::http::{self, HttpError};
+use serde::{Deserialize, Serialize};
+
+// API 响应结构 - 根据 packycode-cost 项目定义
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[allow(dead_code)] // Opus 字段暂未展示
+struct UserApiResponse {
+    #[serde(deserialize_with = "deserialize_string_to_f64")]
+    daily_budget_usd: f64,
+    #[serde(deserialize_with = "deserialize_string_to_f64")]
+    daily_spent_usd: f64,
+    #[serde(deserialize_with = "deserialize_string_to_f64")]
+    monthly_budget_usd: f64,
+    #[serde(deserialize_with = "deserialize_string_to_f64")]
+    monthly_spent_usd: f64,
+    opus_enabled: Option<bool>,
+}
+
+pub struct PackyCodeProvider {
+    url: String,
+}
+
+impl PackyCodeProvider {
+    pub fn new(endpoints: &PackyCodeEndpoints) -> Self {
+        Self {
+            url: endpoints.user_info.clone(),
+        }
+    }
+}
+
+impl QuotaProvider for PackyCodeProvider {
+    fn name(&self) -> &'static str {
+        "packycode"
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.url
+    }
+
+    fn fetch(&self, api_key: &str) -> Result<QuotaUsage, HttpError> {
+        let info: UserApiResponse = http::get_json(&self.url, api_key)?;
+        Ok(QuotaUsage {
+            spent: info.daily_spent_usd,
+            budget: info.daily_budget_usd,
+            remaining: info.daily_budget_usd - info.daily_spent_usd,
+            monthly_spent: Some(info.monthly_spent_usd),
+            monthly_budget: Some(info.monthly_budget_usd),
+            ..QuotaUsage::default()
+        })
+    }
+
+    // PackyCode 控制台地址；指向其他主机（如本地桩服务器）时不加链接
+    fn dashboard_url(&self) -> Option<&'static str> {
+        if self.url.contains("share-api") || self.url.contains("share.packycode.com") {
+            Some("https://share.packycode.com/dashboard")
+        } else if self.url.contains("packycode.com") {
+            Some("https://www.packycode.com/dashboard")
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,34 @@
+// 自动检测时遇到的未知中转站：先按 PackyCode 自定义域名请求 users/info，失败再用 /v1/dashboard/usage
+use super::{GenericProvider, PackyCodeProvider, QuotaProvider, QuotaUsage};
+use crate::config::PackyCodeEndpoints;
+use crate::core::http::HttpError;
+
+pub struct RelayProvider {
+    packycode: PackyCodeProvider,
+    generic: GenericProvider,
+}
+
+impl RelayProvider {
+    pub fn new(endpoints: &PackyCodeEndpoints, base_url: &str) -> Self {
+        Self {
+            packycode: PackyCodeProvider::new(endpoints),
+            generic: GenericProvider::new(base_url),
+        }
+    }
+}
+
+impl QuotaProvider for RelayProvider {
+    fn name(&self) -> &'static str {
+        "relay"
+    }
+
+    fn endpoint(&self) -> &str {
+        self.packycode.endpoint()
+    }
+
+    fn fetch(&self, api_key: &str) -> Result<QuotaUsage, HttpError> {
+        self.packycode
+            .fetch(api_key)
+            .or_else(|_| self.generic.fetch(api_key))
+    }
+}
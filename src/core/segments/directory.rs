@@ -1,26 +1,46 @@
-use super::Segment;
+use super::text_mode::{Icons, EMOJI_ICONS};
+use super::{Segment, SegmentData, SegmentId};
 use crate::config::InputData;
+use crate::core::link::file_url;
 use std::path::Path;
 
 pub struct DirectorySegment {
     enabled: bool,
+    icons: Icons,
 }
 
 impl DirectorySegment {
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
+        Self {
+            enabled,
+            icons: EMOJI_ICONS,
+        }
+    }
+
+    pub fn with_icons(mut self, icons: Icons) -> Self {
+        self.icons = icons;
+        self
     }
 }
 
 impl Segment for DirectorySegment {
-    fn render(&self, input: &InputData) -> String {
+    fn id(&self) -> SegmentId {
+        SegmentId::Directory
+    }
+
+    fn render(&self, input: &InputData) -> SegmentData {
         if !self.enabled {
-            return String::new();
+            return SegmentData::hidden();
         }
 
         let dir_name = get_current_dir_name(&input.workspace.current_dir);
-        // 返回纯目录名，由状态栏统一添加图标与着色
-        format!("{}", dir_name)
+        // 着色由状态栏统一处理
+        SegmentData::new(dir_name.clone())
+            .with_icon(self.icons.directory)
+            .with_priority(80)
+            .with_field("name", dir_name)
+            .with_field("path", input.workspace.current_dir.as_str())
+            .with_link(file_url(&input.workspace.current_dir))
     }
 
     fn enabled(&self) -> bool {
@@ -1,17 +1,44 @@
-use super::Segment;
+use super::text_mode::{Icons, EMOJI_ICONS};
+use super::{Segment, SegmentData, SegmentId, SegmentState};
 use crate::config::InputData;
+use crate::core::cache::MemoryCache;
+use crate::core::link::branch_url;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{Duration, SystemTime};
 
-#[derive(Debug)]
+// 分支、sha、ahead/behind 与远程地址在 HEAD、index、引用或 config 变化前一直有效，
+// daemon 中只在这些文件变化时重新读取；工作区改动没有对应的文件可监视，状态每 2 秒重新读取
+static GIT_DIRS: MemoryCache<PathBuf> = MemoryCache::new(Duration::from_secs(300));
+static GIT_REFS: MemoryCache<(RepoStamp, GitRefs)> = MemoryCache::new(Duration::from_secs(300));
+static GIT_STATUS: MemoryCache<(RepoStamp, GitStatus)> = MemoryCache::new(Duration::from_secs(2));
+
+/// Modification times of the git metadata a render depends on
+type RepoStamp = Vec<Option<SystemTime>>;
+
+#[derive(Debug, Clone)]
 pub struct GitInfo {
     pub branch: String,
     pub status: GitStatus,
     pub ahead: u32,
     pub behind: u32,
     pub sha: Option<String>,
+    /// Web page of the branch on the remote host
+    pub url: Option<String>,
+}
+
+/// The part of `GitInfo` that only changes with the repository's metadata
+#[derive(Debug, Clone)]
+struct GitRefs {
+    branch: String,
+    ahead: u32,
+    behind: u32,
+    sha: Option<String>,
+    url: Option<String>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum GitStatus {
     Clean,
     Dirty,
@@ -21,6 +48,8 @@ pub enum GitStatus {
 pub struct GitSegment {
     enabled: bool,
     show_sha: bool,
+    show_link: bool,
+    icons: Icons,
 }
 
 impl GitSegment {
@@ -28,40 +57,84 @@ impl GitSegment {
         Self {
             enabled,
             show_sha: false,
+            show_link: false,
+            icons: EMOJI_ICONS,
         }
     }
 
+    pub fn with_icons(mut self, icons: Icons) -> Self {
+        self.icons = icons;
+        self
+    }
+
     pub fn with_sha(mut self, show_sha: bool) -> Self {
         self.show_sha = show_sha;
         self
     }
 
+    /// Link the branch to its page on the remote host. Costs two extra git
+    /// calls, so it is only enabled when the terminal shows hyperlinks.
+    pub fn with_link(mut self, show_link: bool) -> Self {
+        self.show_link = show_link;
+        self
+    }
+
     fn get_git_info(&self, working_dir: &str) -> Option<GitInfo> {
         let sanitized_dir = self.sanitize_path(working_dir);
+        let git_dir = GIT_DIRS.get_or_fetch(&sanitized_dir, || self.git_dir(&sanitized_dir))?;
+        let stamp = repo_stamp(&git_dir);
+
+        let key = format!("{}|{}|{}", sanitized_dir, self.show_sha, self.show_link);
+        let refs = match GIT_REFS.get(&key) {
+            Some((cached, refs)) if cached == stamp => refs,
+            _ => {
+                let refs = self.read_refs(&sanitized_dir);
+                GIT_REFS.set(&key, (stamp.clone(), refs.clone()));
+                refs
+            }
+        };
+        let status = match GIT_STATUS.get(&sanitized_dir) {
+            Some((cached, status)) if cached == stamp => status,
+            _ => {
+                let status = self.get_status(&sanitized_dir);
+                GIT_STATUS.set(&sanitized_dir, (stamp, status.clone()));
+                status
+            }
+        };
 
-        // First check if we're in a git repository
-        if !self.is_git_repository(&sanitized_dir) {
-            return None;
-        }
+        Some(GitInfo {
+            branch: refs.branch,
+            status,
+            ahead: refs.ahead,
+            behind: refs.behind,
+            sha: refs.sha,
+            url: refs.url,
+        })
+    }
 
-        let branch = self
-            .get_branch(&sanitized_dir)
-            .unwrap_or_else(|| "detached".to_string());
-        let status = self.get_status(&sanitized_dir);
-        let (ahead, behind) = self.get_ahead_behind(&sanitized_dir);
+    fn read_refs(&self, working_dir: &str) -> GitRefs {
+        let current_branch = self.get_branch(working_dir);
+        let url = match &current_branch {
+            Some(branch) if self.show_link => self
+                .get_remote_url(working_dir, branch)
+                .and_then(|remote| branch_url(&remote, branch)),
+            _ => None,
+        };
+        let branch = current_branch.unwrap_or_else(|| "detached".to_string());
+        let (ahead, behind) = self.get_ahead_behind(working_dir);
         let sha = if self.show_sha {
-            self.get_sha(&sanitized_dir)
+            self.get_sha(working_dir)
         } else {
             None
         };
 
-        Some(GitInfo {
+        GitRefs {
             branch,
-            status,
             ahead,
             behind,
             sha,
-        })
+            url,
+        }
     }
 
     fn sanitize_path(&self, path: &str) -> String {
@@ -90,16 +163,15 @@ impl GitSegment {
             .collect()
     }
 
-    fn is_git_repository(&self, working_dir: &str) -> bool {
+    // 仓库的 .git 目录，不在仓库中时为 None
+    fn git_dir(&self, working_dir: &str) -> Option<PathBuf> {
         let output = Command::new("git")
-            .args(["rev-parse", "--git-dir"])
+            .args(["rev-parse", "--absolute-git-dir"])
             .current_dir(working_dir)
-            .output();
-
-        match output {
-            Ok(output) => output.status.success(),
-            Err(_) => false,
-        }
+            .output()
+            .ok()?;
+        let dir = String::from_utf8(output.stdout).ok()?.trim().to_string();
+        (output.status.success() && !dir.is_empty()).then(|| PathBuf::from(dir))
     }
 
     fn get_branch(&self, working_dir: &str) -> Option<String> {
@@ -121,6 +193,23 @@ impl GitSegment {
         }
     }
 
+    // 分支跟踪的远程仓库地址，未设置上游时使用 origin
+    fn get_remote_url(&self, working_dir: &str, branch: &str) -> Option<String> {
+        let git = |args: &[&str]| -> Option<String> {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(working_dir)
+                .output()
+                .ok()?;
+            let value = String::from_utf8(output.stdout).ok()?.trim().to_string();
+            (output.status.success() && !value.is_empty()).then_some(value)
+        };
+
+        let remote = git(&["config", &format!("branch.{}.remote", branch)])
+            .unwrap_or_else(|| "origin".to_string());
+        git(&["remote", "get-url", &remote])
+    }
+
     fn get_status(&self, working_dir: &str) -> GitStatus {
         let output = Command::new("git")
             .args(["status", "--porcelain"])
@@ -189,25 +278,37 @@ impl GitSegment {
         }
     }
 
+    fn status_state(&self, status: &GitStatus) -> SegmentState {
+        match status {
+            GitStatus::Clean => SegmentState::Good,
+            GitStatus::Dirty => SegmentState::Warning,
+            GitStatus::Conflicts => SegmentState::Critical,
+        }
+    }
+
+    fn status_symbol(&self, status: &GitStatus) -> &'static str {
+        match status {
+            GitStatus::Clean => self.icons.git_clean,
+            GitStatus::Dirty => self.icons.git_dirty,
+            GitStatus::Conflicts => self.icons.git_conflicts,
+        }
+    }
+
     fn format_git_status(&self, info: &GitInfo) -> String {
         let mut parts = Vec::new();
 
-        // Branch name with emoji icon
-        parts.push(format!("🌿 {}", info.branch));
+        // Branch name (the icon is attached by `render`)
+        parts.push(info.branch.clone());
 
-        // Status indicators using simple Unicode symbols
-        match info.status {
-            GitStatus::Clean => parts.push("✓".to_string()),
-            GitStatus::Dirty => parts.push("●".to_string()),
-            GitStatus::Conflicts => parts.push("⚠".to_string()),
-        }
+        // Status indicator from the icon set
+        parts.push(self.status_symbol(&info.status).to_string());
 
         // Remote tracking status with arrows
         if info.ahead > 0 {
-            parts.push(format!("↑{}", info.ahead));
+            parts.push(format!("{}{}", self.icons.git_ahead, info.ahead));
         }
         if info.behind > 0 {
-            parts.push(format!("↓{}", info.behind));
+            parts.push(format!("{}{}", self.icons.git_behind, info.behind));
         }
 
         // Short SHA hash
@@ -219,15 +320,50 @@ impl GitSegment {
     }
 }
 
+// 分支切换、提交、暂存、fetch/push 与 config 修改都会改写其中某个文件或目录
+fn repo_stamp(git_dir: &Path) -> RepoStamp {
+    let modified = |path: PathBuf| fs::metadata(path).and_then(|meta| meta.modified()).ok();
+    let mut stamp: RepoStamp = ["HEAD", "index", "packed-refs", "FETCH_HEAD", "config"]
+        .iter()
+        .chain(&["refs/heads", "refs/remotes"])
+        .map(|name| modified(git_dir.join(name)))
+        .collect();
+    // 远程分支在 refs/remotes/<remote>/ 下更新
+    if let Ok(remotes) = fs::read_dir(git_dir.join("refs/remotes")) {
+        let mut remotes: Vec<PathBuf> = remotes.flatten().map(|entry| entry.path()).collect();
+        remotes.sort();
+        stamp.extend(remotes.into_iter().map(modified));
+    }
+    stamp
+}
+
 impl Segment for GitSegment {
-    fn render(&self, input: &InputData) -> String {
+    fn id(&self) -> SegmentId {
+        SegmentId::Git
+    }
+
+    fn render(&self, input: &InputData) -> SegmentData {
         if !self.enabled {
-            return String::new();
+            return SegmentData::hidden();
         }
 
         match self.get_git_info(&input.workspace.current_dir) {
-            Some(git_info) => self.format_git_status(&git_info),
-            None => String::new(), // Not in a Git repository
+            Some(git_info) => {
+                let data = SegmentData::new(self.format_git_status(&git_info))
+                    .with_icon(self.icons.git)
+                    .with_priority(70)
+                    .with_state(self.status_state(&git_info.status))
+                    .with_field("branch", git_info.branch.as_str())
+                    .with_field("status", self.status_symbol(&git_info.status))
+                    .with_field("ahead", git_info.ahead)
+                    .with_field("behind", git_info.behind)
+                    .with_field("sha", git_info.sha.clone().unwrap_or_default());
+                match git_info.url {
+                    Some(url) => data.with_link(url),
+                    None => data,
+                }
+            }
+            None => SegmentData::hidden(), // Not in a Git repository
         }
     }
 
@@ -235,3 +371,43 @@ impl Segment for GitSegment {
         self.enabled
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    fn touch(path: &Path, secs: u64) {
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+            .unwrap();
+    }
+
+    #[test]
+    fn stamp_follows_metadata_changes() {
+        let git_dir = std::env::temp_dir().join(format!("ccline-git-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&git_dir);
+        fs::create_dir_all(git_dir.join("refs/heads")).unwrap();
+        for name in ["HEAD", "index", "config"] {
+            fs::write(git_dir.join(name), "").unwrap();
+            touch(&git_dir.join(name), 1000);
+        }
+
+        let stamp = repo_stamp(&git_dir);
+        assert_eq!(repo_stamp(&git_dir), stamp);
+
+        // 切换分支改写 HEAD
+        touch(&git_dir.join("HEAD"), 2000);
+        let switched = repo_stamp(&git_dir);
+        assert_ne!(switched, stamp);
+
+        // 第一次 fetch 创建 refs/remotes/origin
+        fs::create_dir_all(git_dir.join("refs/remotes/origin")).unwrap();
+        assert_ne!(repo_stamp(&git_dir), switched);
+
+        let _ = fs::remove_dir_all(&git_dir);
+    }
+}
@@ -1,29 +1,218 @@
 pub mod directory;
+pub mod emoji;
 pub mod git;
 pub mod model;
-pub mod quota;
-pub mod usage;
-pub mod time;
-pub mod emoji;
-pub mod spinner;
+pub mod monthly;
 pub mod network;
+pub mod quota;
 pub mod ranking;
+pub mod spinner;
+pub mod text_mode;
+pub mod time;
+pub mod token;
+pub mod usage;
 
 use crate::config::InputData;
+use crate::core::style::Color;
+use crate::core::template::{Field, Fields};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SegmentId {
+    Spinner,
+    Model,
+    Directory,
+    Git,
+    Usage,
+    Quota,
+    Monthly,
+    Network,
+    Time,
+    Emoji,
+    Ranking,
+    Token,
+}
+
+impl SegmentId {
+    /// Order used when `[layout] order` does not mention a segment
+    pub const DEFAULT_ORDER: [SegmentId; 11] = [
+        SegmentId::Spinner,
+        SegmentId::Model,
+        SegmentId::Directory,
+        SegmentId::Git,
+        SegmentId::Usage,
+        SegmentId::Quota,
+        SegmentId::Monthly,
+        SegmentId::Token,
+        SegmentId::Network,
+        SegmentId::Time,
+        SegmentId::Emoji,
+    ];
+
+    pub fn from_name(name: &str) -> Option<SegmentId> {
+        let id = match name.trim().to_lowercase().as_str() {
+            "spinner" => SegmentId::Spinner,
+            "model" => SegmentId::Model,
+            "directory" | "dir" => SegmentId::Directory,
+            "git" => SegmentId::Git,
+            "usage" => SegmentId::Usage,
+            "quota" => SegmentId::Quota,
+            "monthly" | "month" => SegmentId::Monthly,
+            "network" => SegmentId::Network,
+            "time" => SegmentId::Time,
+            "emoji" => SegmentId::Emoji,
+            "ranking" => SegmentId::Ranking,
+            "token" => SegmentId::Token,
+            _ => return None,
+        };
+        Some(id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SegmentId::Spinner => "spinner",
+            SegmentId::Model => "model",
+            SegmentId::Directory => "directory",
+            SegmentId::Git => "git",
+            SegmentId::Usage => "usage",
+            SegmentId::Quota => "quota",
+            SegmentId::Monthly => "monthly",
+            SegmentId::Network => "network",
+            SegmentId::Time => "time",
+            SegmentId::Emoji => "emoji",
+            SegmentId::Ranking => "ranking",
+            SegmentId::Token => "token",
+        }
+    }
+}
+
+/// Health of the value a segment shows, for outputs that style by state
+/// (JSON `state`, waybar `class`, i3bar `urgent`). Ordered by severity.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SegmentState {
+    #[default]
+    Normal,
+    Good,
+    Warning,
+    Critical,
+}
+
+impl SegmentState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SegmentState::Normal => "normal",
+            SegmentState::Good => "good",
+            SegmentState::Warning => "warning",
+            SegmentState::Critical => "critical",
+        }
+    }
+}
+
+/// Structured output of a segment. Styling and joining are left to the
+/// statusline generator; `fg`/`bg` are only set by segments whose color
+/// depends on their state (e.g. network latency).
+#[derive(Debug, Clone, Default)]
+pub struct SegmentData {
+    pub text: String,
+    pub icon: String,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    /// Higher values are kept longer when space is short
+    pub priority: u8,
+    pub hidden: bool,
+    pub state: SegmentState,
+    /// Last known value shown because the segment missed its deadline
+    pub stale: bool,
+    /// Target URL when the terminal supports OSC 8 hyperlinks
+    pub link: Option<String>,
+    /// Named values for `[formats]` templates, e.g. `{spent}` or `{branch}`
+    pub fields: Fields,
+}
 
-pub trait Segment {
-    fn render(&self, input: &InputData) -> String;
+impl SegmentData {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Nothing to show (segment disabled or no data)
+    pub fn hidden() -> Self {
+        Self {
+            hidden: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = icon.into();
+        self
+    }
+
+    pub fn with_fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    pub fn with_bg(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_state(mut self, state: SegmentState) -> Self {
+        self.state = state;
+        self
+    }
+
+    pub fn with_link(mut self, url: impl Into<String>) -> Self {
+        self.link = Some(url.into());
+        self
+    }
+
+    pub fn with_field(mut self, name: &'static str, value: impl Into<Field>) -> Self {
+        self.fields.insert(name, value.into());
+        self
+    }
+
+    pub fn is_visible(&self) -> bool {
+        !self.hidden && (!self.text.is_empty() || !self.icon.is_empty())
+    }
+
+    /// Icon and text joined by a single space
+    pub fn content(&self) -> String {
+        match (self.icon.is_empty(), self.text.is_empty()) {
+            (true, _) => self.text.clone(),
+            (false, true) => self.icon.clone(),
+            (false, false) => format!("{} {}", self.icon, self.text),
+        }
+    }
+}
+
+// 段落在独立线程中渲染，因此要求 Send
+pub trait Segment: Send {
+    fn id(&self) -> SegmentId;
+    fn render(&self, input: &InputData) -> SegmentData;
     fn enabled(&self) -> bool;
 }
 
 // Re-export all segment types
 pub use directory::DirectorySegment;
+pub use emoji::EmojiSegment;
 pub use git::GitSegment;
 pub use model::ModelSegment;
-pub use quota::QuotaSegment;
-pub use usage::UsageSegment;
-pub use time::TimeSegment;
-pub use emoji::EmojiSegment;
-pub use spinner::SpinnerSegment;
+pub use monthly::MonthlySegment;
 pub use network::NetworkSegment;
+pub use quota::QuotaSegment;
 pub use ranking::RankingSegment;
+pub use spinner::SpinnerSegment;
+pub use text_mode::{get_icons, Icons};
+pub use time::TimeSegment;
+pub use token::TokenSegment;
+pub use usage::UsageSegment;
@@ -1,23 +1,43 @@
-use super::Segment;
+use super::text_mode::{Icons, EMOJI_ICONS};
+use super::{Segment, SegmentData, SegmentId};
 use crate::config::InputData;
 
 pub struct ModelSegment {
     enabled: bool,
+    icons: Icons,
 }
 
 impl ModelSegment {
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
+        Self {
+            enabled,
+            icons: EMOJI_ICONS,
+        }
+    }
+
+    pub fn with_icons(mut self, icons: Icons) -> Self {
+        self.icons = icons;
+        self
     }
 }
 
 impl Segment for ModelSegment {
-    fn render(&self, input: &InputData) -> String {
-        if !self.enabled {
-            return String::new();
+    fn id(&self) -> SegmentId {
+        SegmentId::Model
+    }
+
+    fn render(&self, input: &InputData) -> SegmentData {
+        // 非 Claude Code 调用时没有模型信息
+        if !self.enabled || input.model.display_name.is_empty() {
+            return SegmentData::hidden();
         }
 
-        format!("🤖 {}", self.format_model_name(&input.model.display_name))
+        let name = self.format_model_name(&input.model.display_name);
+        SegmentData::new(name.clone())
+            .with_icon(self.icons.model)
+            .with_priority(90)
+            .with_field("name", name)
+            .with_field("id", input.model.display_name.as_str())
     }
 
     fn enabled(&self) -> bool {
@@ -0,0 +1,241 @@
+use super::text_mode::{Icons, EMOJI_ICONS};
+use super::{QuotaSegment, Segment, SegmentData, SegmentId, SegmentState};
+use crate::config::InputData;
+use crate::core::providers::QuotaUsage;
+use chrono::{Datelike, Local, Months, NaiveDateTime, Utc};
+
+// 本月花费与预算进度：按当月已过时间推算匀速花费，并预测月底总额
+pub struct MonthlySegment {
+    enabled: bool,
+    quota: QuotaSegment,
+    icons: Icons,
+}
+
+/// Month-to-date spend compared with spending evenly through the month
+struct Pace {
+    /// Share of the month that has passed, 0–1
+    elapsed: f64,
+    /// Spend expected by now at an even pace
+    expected: f64,
+    /// Month-end total at the current pace
+    projected: f64,
+}
+
+impl Pace {
+    fn new(spent: f64, budget: f64, now: NaiveDateTime) -> Self {
+        let elapsed = month_elapsed(now);
+        Self {
+            elapsed,
+            expected: budget * elapsed,
+            projected: spent / elapsed,
+        }
+    }
+}
+
+// 当月已过去的比例；月初第一分钟也按一分钟计，避免除以 0
+fn month_elapsed(now: NaiveDateTime) -> f64 {
+    let date = now.date();
+    let start = date.with_day(1).unwrap_or(date);
+    let next = start.checked_add_months(Months::new(1)).unwrap_or(start);
+    let length = (next - start).num_seconds() as f64;
+    let passed = (now - start.and_hms_opt(0, 0, 0).unwrap_or(now)).num_seconds() as f64;
+    if length <= 0.0 {
+        return 1.0;
+    }
+    (passed.max(60.0) / length).min(1.0)
+}
+
+impl MonthlySegment {
+    /// `quota` supplies the provider and cache; its response is reused
+    pub fn new(enabled: bool, quota: QuotaSegment) -> Self {
+        Self {
+            enabled,
+            quota,
+            icons: EMOJI_ICONS,
+        }
+    }
+
+    pub fn with_icons(mut self, icons: Icons) -> Self {
+        self.icons = icons;
+        self
+    }
+
+    fn render_usage(&self, usage: &QuotaUsage, now: NaiveDateTime) -> SegmentData {
+        // 服务商不提供月度数据时不显示
+        let Some(spent) = usage.monthly_spent else {
+            return SegmentData::hidden();
+        };
+        let budget = usage.monthly_budget.unwrap_or(0.0);
+        let symbol = usage.currency_symbol();
+        let pace = Pace::new(spent, budget, now);
+
+        let data = if budget > 0.0 {
+            let percent = spent / budget * 100.0;
+            let over_pace = spent > pace.expected;
+            let indicator = if over_pace {
+                self.icons.pace_over
+            } else {
+                self.icons.pace_under
+            };
+            // 已超预算为 critical，按当前速度月底会超预算为 warning
+            let state = if spent >= budget {
+                SegmentState::Critical
+            } else if pace.projected > budget {
+                SegmentState::Warning
+            } else {
+                SegmentState::Good
+            };
+            SegmentData::new(format!(
+                "Month: {}{:.2}/{}{:.0} ({:.0}%) {} {}{:.0}",
+                symbol, spent, symbol, budget, percent, indicator, symbol, pace.projected
+            ))
+            .with_state(state)
+            .with_field("budget", budget)
+            .with_field("percent", percent)
+            .with_field("pace", indicator)
+        } else {
+            SegmentData::new(format!(
+                "Month: {}{:.2} → {}{:.0}",
+                symbol, spent, symbol, pace.projected
+            ))
+        };
+
+        data.with_icon(self.icons.monthly)
+            .with_priority(45)
+            .with_field("spent", spent)
+            .with_field("expected", pace.expected)
+            .with_field("projected", pace.projected)
+            .with_field("elapsed", pace.elapsed * 100.0)
+            .with_field("currency", symbol.trim())
+    }
+}
+
+impl Segment for MonthlySegment {
+    fn id(&self) -> SegmentId {
+        SegmentId::Monthly
+    }
+
+    fn render(&self, _input: &InputData) -> SegmentData {
+        if !self.enabled {
+            return SegmentData::hidden();
+        }
+        let Some(usage) = self.quota.usage() else {
+            return SegmentData::hidden();
+        };
+        // 按服务商的计费时区判断月份，Anthropic 的报告按 UTC 划分
+        let now = if usage.utc {
+            Utc::now().naive_utc()
+        } else {
+            Local::now().naive_local()
+        };
+        self.render_usage(&usage, now)
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled && self.quota.enabled()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    const JANUARY: f64 = 31.0 * 86400.0;
+
+    fn at(year: i32, month: u32, day: u32, h: u32, m: u32, s: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .and_then(|date| date.and_hms_opt(h, m, s))
+            .unwrap()
+    }
+
+    fn usage(spent: f64, budget: Option<f64>) -> QuotaUsage {
+        QuotaUsage {
+            monthly_spent: Some(spent),
+            monthly_budget: budget,
+            ..QuotaUsage::default()
+        }
+    }
+
+    fn render(spent: f64, budget: Option<f64>, now: NaiveDateTime) -> SegmentData {
+        MonthlySegment::new(true, QuotaSegment::new(false)).render_usage(&usage(spent, budget), now)
+    }
+
+    #[test]
+    fn month_elapsed_first_day_counts_a_minute() {
+        assert_eq!(month_elapsed(at(2026, 1, 1, 0, 0, 0)), 60.0 / JANUARY);
+        assert_eq!(month_elapsed(at(2026, 1, 1, 0, 0, 30)), 60.0 / JANUARY);
+        assert_eq!(month_elapsed(at(2026, 1, 1, 6, 0, 0)), 0.25 / 31.0);
+    }
+
+    #[test]
+    fn month_elapsed_mid_and_last_day() {
+        assert_eq!(month_elapsed(at(2026, 1, 16, 12, 0, 0)), 0.5);
+        // 闰年二月按 29 天计
+        assert_eq!(month_elapsed(at(2028, 2, 15, 0, 0, 0)), 14.0 / 29.0);
+        let last = month_elapsed(at(2026, 1, 31, 23, 59, 59));
+        assert!(last < 1.0 && last > 0.9999, "{}", last);
+        assert_eq!(
+            month_elapsed(at(2026, 12, 31, 23, 59, 59)),
+            1.0 - 1.0 / JANUARY
+        );
+    }
+
+    #[test]
+    fn pace_expects_and_projects() {
+        let pace = Pace::new(40.0, 100.0, at(2026, 1, 16, 12, 0, 0));
+        assert_eq!(pace.elapsed, 0.5);
+        assert_eq!(pace.expected, 50.0);
+        assert_eq!(pace.projected, 80.0);
+
+        let pace = Pace::new(0.0, 0.0, at(2026, 1, 1, 0, 0, 0));
+        assert_eq!(pace.expected, 0.0);
+        assert_eq!(pace.projected, 0.0);
+    }
+
+    #[test]
+    fn under_pace_is_good() {
+        let data = render(40.0, Some(100.0), at(2026, 1, 16, 12, 0, 0));
+        assert_eq!(data.text, "Month: $40.00/$100 (40%) ▼ $80");
+        assert_eq!(data.state, SegmentState::Good);
+    }
+
+    #[test]
+    fn over_pace_warns() {
+        let data = render(60.0, Some(100.0), at(2026, 1, 16, 12, 0, 0));
+        assert_eq!(data.text, "Month: $60.00/$100 (60%) ▲ $120");
+        assert_eq!(data.state, SegmentState::Warning);
+
+        // 月初第一分钟就有花费：推算值很大，同样是 warning
+        let data = render(5.0, Some(100.0), at(2026, 1, 1, 0, 0, 0));
+        assert_eq!(data.state, SegmentState::Warning);
+    }
+
+    #[test]
+    fn over_budget_is_critical() {
+        let data = render(100.0, Some(100.0), at(2026, 1, 31, 23, 59, 59));
+        assert_eq!(data.state, SegmentState::Critical);
+        assert!(
+            data.text.starts_with("Month: $100.00/$100 (100%) ▲"),
+            "{}",
+            data.text
+        );
+    }
+
+    #[test]
+    fn zero_budget_shows_projection_only() {
+        for budget in [None, Some(0.0)] {
+            let data = render(40.0, budget, at(2026, 1, 16, 12, 0, 0));
+            assert_eq!(data.text, "Month: $40.00 → $80");
+            assert_eq!(data.state, SegmentState::Normal);
+            assert!(!data.fields.contains_key("budget"));
+        }
+    }
+
+    #[test]
+    fn hidden_without_monthly_data() {
+        let segment = MonthlySegment::new(true, QuotaSegment::new(false));
+        let data = segment.render_usage(&QuotaUsage::default(), at(2026, 1, 16, 12, 0, 0));
+        assert!(!data.is_visible());
+    }
+}
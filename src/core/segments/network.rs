@@ -1,46 +1,68 @@
-use super::Segment;
-use crate::config::InputData;
+use super::text_mode::{Icons, EMOJI_ICONS};
+use super::{Segment, SegmentData, SegmentId, SegmentState};
+use crate::config::{CacheConfig, InputData};
+use crate::core::cache::DiskCache;
+use crate::core::style::Color;
 use std::process::Command;
+use std::time::Duration;
+
+// 探测结果（包括不可达）默认缓存 30 秒，避免每次渲染都等待 ping
+const CACHE_TTL: Duration = Duration::from_secs(30);
 
 pub struct NetworkSegment {
     enabled: bool,
     target_host: String,
+    icons: Icons,
+    cache: CacheConfig,
 }
 
 impl NetworkSegment {
     pub fn new(enabled: bool) -> Self {
-        Self { 
+        Self {
             enabled,
             target_host: "share.api.packycode.com".to_string(),
+            icons: EMOJI_ICONS,
+            cache: CacheConfig::default(),
         }
     }
 
+    pub fn with_icons(mut self, icons: Icons) -> Self {
+        self.icons = icons;
+        self
+    }
+
+    pub fn with_cache(mut self, cache: CacheConfig) -> Self {
+        self.cache = cache;
+        self
+    }
+
     fn get_network_info(&self) -> NetworkInfo {
-        let (latency, status) = match self.ping_host(&self.target_host) {
+        let host = self.target_host.clone();
+        let latency = DiskCache::new(&self.cache, "network", CACHE_TTL)
+            .get_or_fetch(&self.target_host, "", move || Some(Self::ping_host(&host)))
+            .flatten();
+        let (latency, status) = match latency {
             Some(latency) => (Some(latency), NetworkStatus::Connected),
             None => (None, NetworkStatus::Unreachable),
         };
 
-        NetworkInfo {
-            host: self.target_host.clone(),
-            latency,
-            status,
-        }
+        NetworkInfo { latency, status }
     }
 
-    fn ping_host(&self, host: &str) -> Option<u32> {
+    fn ping_host(host: &str) -> Option<u32> {
         // 临时解决方案：在Windows下使用TCP连接检测替代ping
         if cfg!(target_os = "windows") {
             use std::net::{TcpStream, ToSocketAddrs};
             use std::time::{Duration, Instant};
-            
+
             // 尝试解析地址
             let addr_str = format!("{}:443", host);
             match addr_str.to_socket_addrs() {
                 Ok(mut addrs) => {
                     if let Some(socket_addr) = addrs.next() {
                         let start = Instant::now();
-                        match TcpStream::connect_timeout(&socket_addr, Duration::from_millis(1500)) {
+                        match TcpStream::connect_timeout(&socket_addr, Duration::from_millis(1500))
+                        {
                             Ok(_) => {
                                 let duration = start.elapsed();
                                 return Some((duration.as_millis() as u32).min(9999));
@@ -58,10 +80,10 @@ impl NetworkSegment {
                 .args(["-c", "1", "-W", "3", host])
                 .output()
                 .ok()?;
-                
+
             if output.status.success() {
                 let output_str = String::from_utf8_lossy(&output.stdout);
-                
+
                 // Linux/Unix格式
                 for line in output_str.lines() {
                     if line.contains("time=") {
@@ -79,47 +101,44 @@ impl NetworkSegment {
         }
     }
 
-    fn format_network_info(&self, info: &NetworkInfo) -> String {
+    fn format_network_info(&self, info: &NetworkInfo) -> SegmentData {
         match info.status {
-            NetworkStatus::Connected => {
-                let latency_display = info.latency
-                    .map(|ms| {
-                        // 根据延迟给数值着色
-                        if ms < 100 {
-                            format!("\x1b[32m{}ms\x1b[0m", ms) // 绿色
-                        } else if ms < 300 {
-                            format!("\x1b[33m{}ms\x1b[0m", ms) // 黄色
-                        } else {
-                            format!("\x1b[31m{}ms\x1b[0m", ms) // 红色
-                        }
-                    })
-                    .unwrap_or_else(|| "N/A".to_string());
-
-                // 根据延迟选择图标
-                let icon = info.latency.map(|ms| {
-                    if ms < 100 {
-                        "🟩" // 绿色方块 - 低延迟
-                    } else if ms < 300 {
-                        "🟨" // 黄色方块 - 中等延迟
-                    } else {
-                        "🟥" // 红色方块 - 高延迟
-                    }
-                }).unwrap_or("🟦"); // 蓝色方块 - 未知延迟
-
-                // 只显示图标和延迟，不显示域名
-                format!("{} {}", icon, latency_display)
-            },
-            NetworkStatus::Unreachable => {
-                format!("🟥 \x1b[31mUnreachable\x1b[0m")
-            },
+            NetworkStatus::Connected => match info.latency {
+                // 根据延迟选择图标和颜色
+                Some(ms) if ms < 100 => SegmentData::new(format!("{}ms", ms))
+                    .with_icon(self.icons.network_good) // 低延迟
+                    .with_fg(Color::Green)
+                    .with_state(SegmentState::Good),
+                Some(ms) if ms < 300 => SegmentData::new(format!("{}ms", ms))
+                    .with_icon(self.icons.network_warn) // 中等延迟
+                    .with_fg(Color::Yellow)
+                    .with_state(SegmentState::Warning),
+                Some(ms) => SegmentData::new(format!("{}ms", ms))
+                    .with_icon(self.icons.network_bad) // 高延迟
+                    .with_fg(Color::Red)
+                    .with_state(SegmentState::Critical),
+                None => SegmentData::new("N/A").with_icon(self.icons.network_unknown), // 未知延迟
+            }
+            .with_field("status", "connected"),
+            NetworkStatus::Unreachable => SegmentData::new("Unreachable")
+                .with_icon(self.icons.network_bad)
+                .with_fg(Color::Red)
+                .with_state(SegmentState::Critical)
+                .with_field("status", "unreachable"),
         }
+        .with_priority(30)
+        .with_field("latency", info.latency.unwrap_or(0))
     }
 }
 
 impl Segment for NetworkSegment {
-    fn render(&self, _input: &InputData) -> String {
+    fn id(&self) -> SegmentId {
+        SegmentId::Network
+    }
+
+    fn render(&self, _input: &InputData) -> SegmentData {
         if !self.enabled {
-            return String::new();
+            return SegmentData::hidden();
         }
 
         let network_info = self.get_network_info();
@@ -133,13 +152,12 @@ impl Segment for NetworkSegment {
 
 #[derive(Debug)]
 struct NetworkInfo {
-    host: String,
     latency: Option<u32>, // 延迟(毫秒)
     status: NetworkStatus,
 }
 
 #[derive(Debug, PartialEq)]
 enum NetworkStatus {
-    Connected,     // 已连接且可达
-    Unreachable,   // 不可达
+    Connected,   // 已连接且可达
+    Unreachable, // 不可达
 }
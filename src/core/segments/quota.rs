@@ -1,366 +1,319 @@
-use super::{Segment, RankingSegment};
-use crate::config::InputData;
-use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
-use std::time::{Duration, SystemTime};
-use std::sync::{Arc, Mutex};
-
-// 全局缓存，避免重复 API 调用
-lazy_static::lazy_static! {
-    static ref API_CACHE: Arc<Mutex<Option<(UserApiResponse, SystemTime)>>> = Arc::new(Mutex::new(None));
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct ApiQuota {
-    remaining: f64,
-    total: f64,
-    used: f64,
-    timestamp: SystemTime,
-}
-
-// API 响应结构 - 根据 packycode-cost 项目定义
-#[derive(Debug, Deserialize, Clone)]
-struct UserApiResponse {
-    #[serde(deserialize_with = "deserialize_string_to_f64")]
-    daily_budget_usd: f64,
-    #[serde(deserialize_with = "deserialize_string_to_f64")]
-    daily_spent_usd: f64,
-    #[serde(deserialize_with = "deserialize_string_to_f64")]
-    monthly_budget_usd: f64,
-    #[serde(deserialize_with = "deserialize_string_to_f64")]
-    monthly_spent_usd: f64,
-    opus_enabled: Option<bool>,
-}
-
-// 自定义反序列化函数，将字符串转换为 f64
-fn deserialize_string_to_f64<'de, D>(deserializer: D) -> Result<f64, D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    use serde::de;
-    
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum StringOrFloat {
-        String(String),
-        Float(f64),
-    }
-    
-    match StringOrFloat::deserialize(deserializer)? {
-        StringOrFloat::String(s) => s.parse::<f64>()
-            .map_err(|_| de::Error::custom("Failed to parse string as f64")),
-        StringOrFloat::Float(f) => Ok(f),
-    }
-}
-
-#[derive(Debug, Deserialize)]
-struct AnthropicUsageResponse {
-    #[serde(rename = "remaining_credit_in_usd")]
-    remaining: f64,
-    #[serde(rename = "credit_limit_in_usd")]
-    limit: f64,
-}
-
-#[derive(Debug, Deserialize)]
-struct ClaudeCodeSettings {
-    env: Option<ClaudeCodeEnv>,
-    info_url: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct ClaudeCodeEnv {
-    #[serde(rename = "ANTHROPIC_BASE_URL")]
-    base_url: Option<String>,
-    #[serde(rename = "ANTHROPIC_AUTH_TOKEN")]
-    auth_token: Option<String>,
-    #[serde(rename = "ANTHROPIC_API_KEY")]
-    api_key: Option<String>,
-}
+use super::text_mode::{Icons, EMOJI_ICONS};
+use super::{RankingSegment, Segment, SegmentData, SegmentId, SegmentState};
+use crate::config::{
+    ApiSettings, BarsConfig, CacheConfig, InputData, PackyCodeConfig, QuotaConfig, SharedJwtToken,
+    TokenError,
+};
+use crate::core::bar;
+use crate::core::cache::DiskCache;
+use crate::core::providers::{ProviderKind, QuotaProvider, QuotaUsage};
+use std::sync::Arc;
+use std::time::Duration;
+
+// 配额数据默认缓存 30 秒，避免重复 API 调用
+const CACHE_TTL: Duration = Duration::from_secs(30);
 
 pub struct QuotaSegment {
     enabled: bool,
-    api_key: Option<String>,
-    base_url: String,
-    info_url: Option<String>,
-    jwt_token: Option<String>,
+    settings: ApiSettings,
+    packycode: PackyCodeConfig,
+    quota: QuotaConfig,
+    token: SharedJwtToken,
+    icons: Icons,
+    bar: Option<BarsConfig>,
+    cache: CacheConfig,
 }
 
 impl QuotaSegment {
     pub fn new(enabled: bool) -> Self {
-        let (api_key, base_url, info_url) = Self::load_api_config();
-        Self {
-            enabled,
-            api_key,
-            base_url,
-            info_url,
-            jwt_token: None,
-        }
+        Self::new_with_config(enabled, SharedJwtToken::default())
     }
 
-    pub fn new_with_config(enabled: bool, jwt_token: Option<String>) -> Self {
-        let (api_key, base_url, info_url) = Self::load_api_config();
+    pub fn new_with_config(enabled: bool, token: SharedJwtToken) -> Self {
         Self {
             enabled,
-            api_key,
-            base_url,
-            info_url,
-            jwt_token,
+            settings: ApiSettings::load(),
+            packycode: PackyCodeConfig::default(),
+            quota: QuotaConfig::default(),
+            token,
+            icons: EMOJI_ICONS,
+            bar: None,
+            cache: CacheConfig::default(),
         }
     }
 
-    fn load_api_config() -> (Option<String>, String, Option<String>) {
-        // Try multiple sources for API configuration
-
-        // 1. Claude Code settings.json
-        if let Some(config_dir) = Self::get_claude_config_dir() {
-            let settings_path = config_dir.join("settings.json");
-            if let Ok(content) = fs::read_to_string(&settings_path) {
-                if let Ok(settings) = serde_json::from_str::<ClaudeCodeSettings>(&content) {
-                    let info_url = settings.info_url.clone();
-                    if let Some(env) = settings.env {
-                        let api_key = env.auth_token.or(env.api_key);
-                        let base_url = env
-                            .base_url
-                            .unwrap_or_else(|| "https://api.anthropic.com".to_string());
-                        if api_key.is_some() {
-                            return (api_key, base_url, info_url);
-                        }
-                    }
-                }
-            }
-        }
-
-        // 2. Environment variable
-        let api_key = std::env::var("ANTHROPIC_API_KEY")
-            .ok()
-            .or_else(|| std::env::var("ANTHROPIC_AUTH_TOKEN").ok());
-
-        let base_url = std::env::var("ANTHROPIC_BASE_URL")
-            .unwrap_or_else(|_| "https://api.anthropic.com".to_string());
-
-        let info_url = std::env::var("INFO_URL").ok();
+    pub fn with_icons(mut self, icons: Icons) -> Self {
+        self.icons = icons;
+        self
+    }
 
-        // 3. Claude Code api_key file
-        if api_key.is_none() {
-            if let Some(home) = dirs::home_dir() {
-                let config_path = home.join(".claude").join("api_key");
-                if let Ok(key) = fs::read_to_string(config_path) {
-                    return (Some(key.trim().to_string()), base_url, info_url);
-                }
-            }
-        }
+    /// Show a daily-budget bar in front of today's spend
+    pub fn with_bar(mut self, bar: Option<BarsConfig>) -> Self {
+        self.bar = bar;
+        self
+    }
 
-        (api_key, base_url, info_url)
+    pub fn with_cache(mut self, cache: CacheConfig) -> Self {
+        self.cache = cache;
+        self
     }
 
-    fn get_claude_config_dir() -> Option<PathBuf> {
-        // Claude Code config directory is ~/.claude
-        dirs::home_dir().map(|home| home.join(".claude"))
+    /// `[packycode]` endpoints for the API base URL in use
+    pub fn with_packycode(mut self, config: &PackyCodeConfig) -> Self {
+        self.packycode = config.clone();
+        self
     }
 
-    // 获取用户信息 API 数据
-    fn fetch_user_info_api(api_key: &str, base_url: &str) -> Option<UserApiResponse> {
-        // 优先使用配置的 info_url
-        let (_, _, info_url) = Self::load_api_config();
-        
-        let url = if let Some(info_url) = info_url {
-            // 如果配置了 info_url，直接使用
-            info_url
-        } else if base_url.starts_with("https://share-api") {
-            // share-api 的情况，使用正确的端点
-            "https://share.packycode.com/api/backend/users/info".to_string()
-        } else if base_url.contains("packycode.com") {
-            // 公交车模式
-            format!("{}/api/backend/users/info", base_url)
-        } else {
-            // 默认处理
-            format!("{}/api/backend/users/info", base_url)
-        };
-        
-        let client = reqwest::blocking::Client::new();
-        let response = client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", api_key))
-            .header("accept", "application/json")
-            .timeout(Duration::from_secs(2))
-            .send()
-            .ok()?;
+    /// `[quota]` provider selection
+    pub fn with_quota(mut self, config: &QuotaConfig) -> Self {
+        self.quota = config.clone();
+        self
+    }
 
-        if response.status().is_success() {
-            response.json().ok()
-        } else {
-            None
-        }
+    fn provider_kind(&self) -> ProviderKind {
+        ProviderKind::from_config(&self.quota, &self.settings, &self.packycode)
     }
 
-    // 共享的 API 获取函数，带缓存
-    fn fetch_user_info_cached() -> Option<UserApiResponse> {
-        const CACHE_DURATION: Duration = Duration::from_secs(30); // 30秒缓存
+    fn provider(&self) -> Arc<dyn QuotaProvider> {
+        self.provider_kind()
+            .provider(&self.quota, &self.settings, &self.packycode)
+    }
 
-        // 检查缓存
-        if let Ok(cache) = API_CACHE.lock() {
-            if let Some((cached_info, cached_time)) = cache.as_ref() {
-                if cached_time.elapsed().unwrap_or(CACHE_DURATION) < CACHE_DURATION {
-                    return Some(cached_info.clone());
-                }
-            }
-        }
+    // 带缓存的配额请求，按服务商、地址与实际发出的凭据区分
+    fn fetch_quota(&self, provider: Arc<dyn QuotaProvider>) -> Option<QuotaUsage> {
+        let key = provider.credential(self.settings.api_key.as_deref())?;
+        let endpoint = format!("{}:{}", provider.name(), provider.endpoint());
+        let cache_key = provider.cache_key(&key);
+        DiskCache::new(&self.cache, "quota", CACHE_TTL).get_or_fetch(
+            &endpoint,
+            &cache_key,
+            move || provider.fetch(&key).ok(),
+        )
+    }
 
-        // 缓存过期或不存在，重新获取
-        let (api_key, base_url, _) = Self::load_api_config();
-        let api_key = api_key?;
-        
-        // 使用统一的 backend/users/info API
-        let user_info = Self::fetch_user_info_api(&api_key, &base_url)?;
-        
-        // 更新缓存
-        if let Ok(mut cache) = API_CACHE.lock() {
-            *cache = Some((user_info.clone(), SystemTime::now()));
-        }
-        
-        Some(user_info)
+    /// Spend from the configured provider, shared with the monthly segment
+    pub fn usage(&self) -> Option<QuotaUsage> {
+        self.fetch_quota(self.provider())
     }
 
-    fn fetch_quota(&self) -> Option<ApiQuota> {
-        // 使用统一的 backend/users/info API
-        if let Some(ref api_key) = self.api_key {
-            if let Some(user_info) = Self::fetch_user_info_api(api_key, &self.base_url) {
-                // 直接使用 API 返回的数据
-                let quota = ApiQuota {
-                    remaining: user_info.daily_budget_usd - user_info.daily_spent_usd,
-                    total: user_info.daily_budget_usd,
-                    used: user_info.daily_spent_usd,
-                    timestamp: SystemTime::now(),
-                };
-                return Some(quota);
-            }
-        }
-        
-        // 使用缓存的 API 调用（回退方案）
-        if let Some(user_info) = Self::fetch_user_info_cached() {
-            let quota = ApiQuota {
-                remaining: user_info.daily_budget_usd - user_info.daily_spent_usd,
-                total: user_info.daily_budget_usd,
-                used: user_info.daily_spent_usd,
-                timestamp: SystemTime::now(),
-            };
-            return Some(quota);
-        }
+    fn format_quota(&self, quota: &QuotaUsage, provider: &dyn QuotaProvider) -> SegmentData {
+        // 显示今日花费金额
+        let daily_spent = quota.spent;
 
-        // Fallback to standard Anthropic API
-        let api_key = self.api_key.as_ref()?;
-        let url = if self.base_url.contains("api.anthropic.com") {
-            format!("{}/v1/dashboard/usage", self.base_url)
+        // Choose icon based on spending amount
+        let emoji = if daily_spent < 5.0 {
+            self.icons.quota_low // very low spending
+        } else if daily_spent < 15.0 {
+            self.icons.quota_medium // moderate spending
+        } else if daily_spent < 30.0 {
+            self.icons.quota_high // high spending
         } else {
-            // For proxy/custom endpoints, try common patterns
-            format!("{}/v1/dashboard/usage", self.base_url)
+            self.icons.quota_critical // very high spending
         };
 
-        let client = reqwest::blocking::Client::new();
-        let mut request = client.get(&url).timeout(Duration::from_secs(2));
-
-        // Handle different auth header formats based on the endpoint
-        if self.base_url.contains("api.anthropic.com") {
-            request = request
-                .header("x-api-key", api_key)
-                .header("anthropic-version", "2023-06-01");
+        // 尝试获取排名信息和垃圾话；排名只有 PackyCode 提供，token 不可用时说明原因
+        let kind = self.provider_kind();
+        let ranking = if kind.may_be_packycode() {
+            self.get_ranking_info().or_else(|err| match err {
+                // 未知中转站没有 token 多半不是 PackyCode，不提示
+                TokenError::Missing if kind == ProviderKind::Relay => Ok(None),
+                err => Err(err),
+            })
         } else {
-            // For custom endpoints, try both header formats
-            request = request
-                .header("Authorization", format!("Bearer {}", api_key))
-                .header("x-api-key", api_key);
-        }
-
-        let response = request.send().ok()?;
+            Ok(None)
+        };
 
-        if response.status().is_success() {
-            let usage: AnthropicUsageResponse = response.json().ok()?;
+        // 今日花费占每日预算的比例；没有预算时不显示进度条
+        let budget_percent = if quota.budget > 0.0 {
+            quota.spent / quota.budget * 100.0
+        } else {
+            0.0
+        };
+        let bar_text = if quota.budget > 0.0 {
+            bar::render_bar(
+                budget_percent / 100.0,
+                self.bar.as_ref().unwrap_or(&BarsConfig::default()),
+            )
+        } else {
+            String::new()
+        };
+        let bar_prefix = match &self.bar {
+            Some(_) if !bar_text.is_empty() => format!("{} ", bar_text),
+            _ => String::new(),
+        };
 
-            let quota = ApiQuota {
-                remaining: usage.remaining,
-                total: usage.limit,
-                used: usage.limit - usage.remaining,
-                timestamp: SystemTime::now(),
+        // 格式化显示：emoji Today: $花费 排名图标 排名数字 | 垃圾话
+        let symbol = quota.currency_symbol();
+        let balance_only = quota.spent == 0.0 && quota.budget <= 0.0;
+        let text = if balance_only && quota.unlimited {
+            // 不限额度：只能显示累计用量
+            format!("Used: {}{:.2}", symbol, quota.total_spent.unwrap_or(0.0))
+        } else if balance_only && (quota.remaining > 0.0 || quota.total_spent.is_some()) {
+            // 只有余额的接口
+            format!("Balance: {}{:.2}", symbol, quota.remaining)
+        } else if let Ok(Some(ranking)) = &ranking {
+            let rank_display = if ranking.icon.is_empty() {
+                ranking.rank.to_string()
+            } else {
+                format!("{} {}", ranking.icon, ranking.rank)
             };
-
-            Some(quota)
+            format!(
+                "Today: {}{}{:.2} {} | {}",
+                bar_prefix, symbol, daily_spent, rank_display, ranking.talk
+            )
+        } else if let Err(err) = &ranking {
+            format!(
+                "Today: {}{}{:.2} | 排名已停用: {}",
+                bar_prefix, symbol, daily_spent, err
+            )
+        } else if let Some(tokens) = quota.tokens {
+            format!(
+                "Today: {}{}{:.2} · {} tokens",
+                bar_prefix,
+                symbol,
+                daily_spent,
+                format_tokens(tokens)
+            )
         } else {
-            None
-        }
-    }
-
-    fn format_quota(&self, quota: &ApiQuota) -> String {
-        // 显示今日花费金额
-        let daily_spent = quota.used;
+            format!("Today: {}{}{:.2}", bar_prefix, symbol, daily_spent)
+        };
 
-        // Choose emoji based on spending amount
-        let emoji = if daily_spent < 5.0 {
-            "💚" // Green - very low spending
-        } else if daily_spent < 15.0 {
-            "💛" // Yellow - moderate spending
-        } else if daily_spent < 30.0 {
-            "🧡" // Orange - high spending
+        // 按预算使用比例判断状态，没有预算时保持 normal
+        let state = if quota.budget <= 0.0 {
+            SegmentState::Normal
+        } else if budget_percent >= 100.0 {
+            SegmentState::Critical
+        } else if budget_percent >= 80.0 {
+            SegmentState::Warning
         } else {
-            "❤️" // Red - very high spending
+            SegmentState::Good
         };
 
-        // 尝试获取排名信息和垃圾话
-        let ranking_info = self.get_ranking_info();
-
-        // 格式化显示：emoji Today: $花费 排名图标 排名数字 | 垃圾话
-        if let Some((rank_display, talk, _gap_info)) = ranking_info {
-            format!("{} Today: ${:.2} {} | {}", emoji, daily_spent, rank_display, talk)
-        } else {
-            format!("{} Today: ${:.2}", emoji, daily_spent)
+        let mut data = SegmentData::new(text)
+            .with_icon(emoji)
+            .with_priority(50)
+            .with_state(state)
+            .with_field("spent", quota.spent)
+            .with_field("budget", quota.budget)
+            .with_field("remaining", quota.remaining)
+            .with_field("currency", symbol.trim())
+            .with_field("used", quota.total_spent.unwrap_or(0.0))
+            .with_field("tokens", quota.tokens.unwrap_or(0) as f64)
+            .with_field("bar", bar_text);
+        if let Some(color) = self
+            .bar
+            .as_ref()
+            .filter(|_| quota.budget > 0.0)
+            .and_then(|config| bar::stop_color(budget_percent, config))
+        {
+            data = data.with_fg(color);
+        }
+        if let Some(url) = provider.dashboard_url() {
+            data = data.with_link(url);
         }
+        if let Err(err) = &ranking {
+            data = data.with_field("rank_error", err.to_string());
+        }
+        if let Ok(Some(ranking)) = ranking {
+            data = data
+                .with_field("rank", ranking.rank)
+                .with_field("total", ranking.total)
+                .with_field("rank_icon", ranking.icon)
+                .with_field("talk", ranking.talk)
+                .with_field("gap", ranking.gap.unwrap_or(0.0));
+        }
+        data
     }
 
-    fn get_ranking_info(&self) -> Option<(String, String, Option<String>)> {
+    /// The rank, `None` when it could not be fetched, or why there is no
+    /// token to fetch it with
+    fn get_ranking_info(&self) -> Result<Option<RankingSummary>, TokenError> {
         // 创建一个临时的RankingSegment来获取排名信息，传入JWT token
-        let ranking_segment = RankingSegment::new_with_token(true, self.jwt_token.clone());
-        if let Some((rank, total)) = ranking_segment.get_current_ranking() {
-            // 根据排名选择图标和颜色
-            let (icon, color) = match rank {
-                1 => ("🥇", "\x1b[33m"), // 金色
-                2 => ("🥈", "\x1b[37m"), // 银色
-                3 => ("🥉", "\x1b[31m"), // 铜色
-                _ => ("📊", "\x1b[36m"), // 青色
-            };
+        let ranking_segment = RankingSegment::new_with_token(true, self.token.clone())
+            .with_endpoints(
+                self.packycode
+                    .endpoints(&self.settings.base_url, self.settings.info_url.as_deref()),
+            )
+            .with_icons(self.icons)
+            .with_cache(self.cache.clone());
+        ranking_segment.jwt_token().map_err(Clone::clone)?;
+        let Some((rank, total)) = ranking_segment.get_current_ranking() else {
+            return Ok(None);
+        };
 
-            let rank_display = format!("{} {}{}\x1b[0m", icon, color, rank);
-            let trash_talk = RankingSegment::get_trash_talk_by_rank(rank, total).to_string();
+        // 根据排名选择图标
+        let (icon, _) = ranking_segment.rank_icon_and_color(rank);
+        let talk = RankingSegment::get_trash_talk_by_rank(rank, total);
 
-            // 获取与上一名的差距
-            let gap_info = if rank > 1 {
-                ranking_segment.get_gap_to_previous()
-            } else {
-                None
-            };
-
-            Some((rank_display, trash_talk, gap_info))
+        // 获取与上一名的差距
+        let gap = if rank > 1 {
+            ranking_segment.get_gap_to_previous()
         } else {
             None
-        }
+        };
+
+        Ok(Some(RankingSummary {
+            rank,
+            total,
+            icon,
+            talk,
+            gap,
+        }))
+    }
+}
+
+// 嵌入在 quota 段落中的排名信息
+struct RankingSummary {
+    rank: usize,
+    total: usize,
+    icon: &'static str,
+    talk: &'static str,
+    gap: Option<f64>,
+}
+
+// 12345 -> 12.3k，1234567 -> 1.2M
+fn format_tokens(tokens: u64) -> String {
+    if tokens >= 1_000_000 {
+        format!("{:.1}M", tokens as f64 / 1_000_000.0)
+    } else if tokens >= 1000 {
+        format!("{:.1}k", tokens as f64 / 1000.0)
+    } else {
+        tokens.to_string()
     }
 }
 
 impl Segment for QuotaSegment {
-    fn render(&self, _input: &InputData) -> String {
-        if !self.enabled || self.api_key.is_none() {
-            return String::new();
+    fn id(&self) -> SegmentId {
+        SegmentId::Quota
+    }
+
+    fn render(&self, _input: &InputData) -> SegmentData {
+        let provider = self.provider();
+        if !self.enabled
+            || provider
+                .credential(self.settings.api_key.as_deref())
+                .is_none()
+        {
+            return SegmentData::hidden();
         }
 
         // Try to fetch quota (from cache or API)
-        if let Some(quota) = self.fetch_quota() {
-            self.format_quota(&quota)
+        if let Some(quota) = self.fetch_quota(Arc::clone(&provider)) {
+            self.format_quota(&quota, provider.as_ref())
         } else {
             // If we can't get quota, show unknown
-            "◔ Quota: N/A".to_string()
+            SegmentData::new("Quota: N/A")
+                .with_icon(self.icons.quota_unknown)
+                .with_priority(50)
+                .with_state(SegmentState::Warning)
         }
     }
 
     fn enabled(&self) -> bool {
-        self.enabled && self.api_key.is_some()
+        self.enabled
+            && self
+                .provider()
+                .credential(self.settings.api_key.as_deref())
+                .is_some()
     }
-}
\ No newline at end of file
+}
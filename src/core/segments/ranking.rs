@@ -1,35 +1,76 @@
-use super::Segment;
-use crate::config::InputData;
+use super::text_mode::{Icons, EMOJI_ICONS};
+use super::{Segment, SegmentData, SegmentId, SegmentState};
+use crate::config::{
+    CacheConfig, InputData, JwtClaims, PackyCodeEndpoints, SharedJwtToken, TokenError,
+};
+use crate::core::cache::{DiskCache, FetchError};
+use crate::core::http::{self, HttpError};
+use crate::core::style::Color;
+use chrono::Utc;
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
-use std::process::Command;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// 排名数据默认缓存 60 秒，按 token 区分；quota 与 ranking 共用
+const CACHE_TTL: Duration = Duration::from_secs(60);
 
 pub struct RankingSegment {
     enabled: bool,
-    jwt_token: String,
+    token: SharedJwtToken,
+    endpoints: PackyCodeEndpoints,
+    // 首次使用时解析，避免在主线程中执行 jwt_token_command
+    jwt_token: OnceLock<Result<String, TokenError>>,
+    icons: Icons,
+    cache: CacheConfig,
 }
 
 impl RankingSegment {
     pub fn new(enabled: bool) -> Self {
-        Self {
-            enabled,
-            // 使用默认的JWT token，实际应该从配置中传入
-            jwt_token: "eyJ0eXAiOiJKV1QiLCJhbGciOiJIUzI1NiJ9.eyJzdWIiOiI3OGM2NTM3OC0xN2RhLTRkNzAtOTcyMC05ZjVhNGNkMmZhOGMiLCJpc3MiOiJma2NvZGUtYXBpIiwiYXVkIjoiZmtjb2RlLXVzZXJzIiwiZXhwIjoxNzU2NzgyMzIwLCJpYXQiOjE3NTYxNzc1MjAsIm5iZiI6MTc1NjE3NzUyMCwianRpIjoiZTAwZTVkY2EtYTgyYi00OTgwLTlkMmUtNDMwMWZkODViMzQ0IiwidXNlcl9pZCI6Ijc4YzY1Mzc4LTE3ZGEtNGQ3MC05NzIwLTlmNWE0Y2QyZmE4YyIsImVtYWlsIjoiMTA1MjYwNzQyM0BxcS5jb20iLCJ1c2VybmFtZSI6IjEwNTI2MDc0MjMiLCJ1c2VyX3R5cGUiOiJ1c2VyIiwicGVybWlzc2lvbnMiOlsiYXBpOnVzZSIsInByb2ZpbGU6cmVhZCIsInByb2ZpbGU6dXBkYXRlIiwidXNhZ2U6cmVhZCJdLCJzZXNzaW9uX2lkIjoiMjA2ZGNhNzgtYWU4MS00ODRlLWIyMTItNWYyODdmZmU2OWQ3In0.7FueQXA_3qlOJgzWQY6-gjKzvPlHw-V9f4jC7TN_U6w".to_string(),
-        }
+        Self::new_with_token(enabled, SharedJwtToken::default())
     }
 
-    pub fn new_with_token(enabled: bool, jwt_token: Option<String>) -> Self {
+    pub fn new_with_token(enabled: bool, token: SharedJwtToken) -> Self {
         Self {
             enabled,
-            // 优先使用配置中的token，然后是环境变量，最后是默认token
-            jwt_token: jwt_token
-                .or_else(|| std::env::var("PACKYCODE_JWT_TOKEN").ok())
-                .unwrap_or_else(|| {
-                    "eyJ0eXAiOiJKV1QiLCJhbGciOiJIUzI1NiJ9.eyJzdWIiOiI3OGM2NTM3OC0xN2RhLTRkNzAtOTcyMC05ZjVhNGNkMmZhOGMiLCJpc3MiOiJma2NvZGUtYXBpIiwiYXVkIjoiZmtjb2RlLXVzZXJzIiwiZXhwIjoxNzU2NzgyMzIwLCJpYXQiOjE3NTYxNzc1MjAsIm5iZiI6MTc1NjE3NzUyMCwianRpIjoiZTAwZTVkY2EtYTgyYi00OTgwLTlkMmUtNDMwMWZkODViMzQ0IiwidXNlcl9pZCI6Ijc4YzY1Mzc4LTE3ZGEtNGQ3MC05NzIwLTlmNWE0Y2QyZmE4YyIsImVtYWlsIjoiMTA1MjYwNzQyM0BxcS5jb20iLCJ1c2VybmFtZSI6IjEwNTI2MDc0MjMiLCJ1c2VyX3R5cGUiOiJ1c2VyIiwicGVybWlzc2lvbnMiOlsiYXBpOnVzZSIsInByb2ZpbGU6cmVhZCIsInByb2ZpbGU6dXBkYXRlIiwidXNhZ2U6cmVhZCJdLCJzZXNzaW9uX2lkIjoiMjA2ZGNhNzgtYWU4MS00ODRlLWIyMTItNWYyODdmZmU2OWQ3In0.7FueQXA_3qlOJgzWQY6-gjKzvPlHw-V9f4jC7TN_U6w".to_string()
-                }),
+            token,
+            endpoints: PackyCodeEndpoints::default(),
+            jwt_token: OnceLock::new(),
+            icons: EMOJI_ICONS,
+            cache: CacheConfig::default(),
         }
     }
 
+    pub fn with_icons(mut self, icons: Icons) -> Self {
+        self.icons = icons;
+        self
+    }
+
+    pub fn with_cache(mut self, cache: CacheConfig) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    pub fn with_endpoints(mut self, endpoints: PackyCodeEndpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Token from the first configured source, or why there is none.
+    /// An expired token is never sent.
+    pub fn jwt_token(&self) -> Result<&str, &TokenError> {
+        self.jwt_token
+            .get_or_init(|| {
+                let token = self.token.resolve()?;
+                let now = Utc::now().timestamp();
+                match JwtClaims::decode(&token) {
+                    Some(claims) if claims.is_expired(now) => Err(TokenError::Expired),
+                    _ => Ok(token),
+                }
+            })
+            .as_deref()
+    }
+
     // 静态方法：根据排名获取垃圾话，供其他segment使用
     pub fn get_trash_talk_by_rank(rank: usize, total: usize) -> &'static str {
         match rank {
@@ -46,20 +87,22 @@ impl RankingSegment {
         let ranking_info = self.get_ranking_info();
         match ranking_info.status {
             RankingStatus::Success => {
-                if let (Some(rank), Some(total)) = (ranking_info.current_rank, ranking_info.total_participants) {
+                if let (Some(rank), Some(total)) =
+                    (ranking_info.current_rank, ranking_info.total_participants)
+                {
                     Some((rank, total))
                 } else {
                     None
                 }
             }
-            RankingStatus::Error => None,
+            RankingStatus::Error(_) => None,
         }
     }
 
-    // 获取与上一名的差距
-    pub fn get_gap_to_previous(&self) -> Option<String> {
+    // 获取与上一名的差距（美元）
+    pub fn get_gap_to_previous(&self) -> Option<f64> {
         // 获取同行消费数据
-        let peer_data = self.fetch_peer_spending_data()?;
+        let peer_data = self.fetch_peer_spending_data().ok()?;
         let current_user_spending = self.get_current_user_spending().unwrap_or(0.0);
 
         if peer_data.is_empty() {
@@ -67,7 +110,8 @@ impl RankingSegment {
         }
 
         // 解析所有消费数据并排序
-        let mut all_spending: Vec<f64> = peer_data.iter()
+        let mut all_spending: Vec<f64> = peer_data
+            .iter()
             .filter_map(|peer| peer.spent_usd_today.parse::<f64>().ok())
             .collect();
 
@@ -78,7 +122,8 @@ impl RankingSegment {
         all_spending.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
 
         // 找到当前用户的位置
-        let current_position = all_spending.iter()
+        let current_position = all_spending
+            .iter()
             .position(|&spending| (spending - current_user_spending).abs() < 0.01)?;
 
         // 如果是第一名，不显示差距
@@ -88,170 +133,103 @@ impl RankingSegment {
 
         // 获取上一名的消费金额
         let previous_spending = all_spending[current_position - 1];
-        let gap = previous_spending - current_user_spending;
-
-        // 根据差距大小选择颜色
-        let color = if gap < 5.0 {
-            "\x1b[32m" // 绿色 - 差距很小
-        } else if gap < 15.0 {
-            "\x1b[33m" // 黄色 - 差距中等
-        } else if gap < 30.0 {
-            "\x1b[31m" // 红色 - 差距较大
-        } else {
-            "\x1b[35m" // 紫色 - 差距巨大
-        };
-
-        Some(format!("(距上一名{}${:.2}\x1b[0m)", color, gap))
+        Some(previous_spending - current_user_spending)
     }
 
     // 获取同行消费原始数据
-    fn fetch_peer_spending_data(&self) -> Option<Vec<PeerRecord>> {
-        let output = Command::new("curl")
-            .args([
-                "-s",
-                "-H", &format!("Authorization: Bearer {}", self.jwt_token),
-                "-H", "Content-Type: application/json",
-                "https://share.packycode.com/api/backend/accounts/peer-spending/today"
-            ])
-            .output()
-            .ok()?;
-
-        if !output.status.success() {
-            return None;
-        }
+    fn fetch_peer_spending_data(&self) -> Result<Vec<PeerRecord>, RankingError> {
+        let token = self
+            .jwt_token()
+            .map_err(|err| RankingError::Token(err.clone()))?;
+        let (url, owned) = (self.endpoints.peer_spending.clone(), token.to_string());
+        DiskCache::new(&self.cache, "ranking", CACHE_TTL)
+            .try_get_or_fetch(&self.endpoints.peer_spending, token, move || {
+                Self::request_peer_spending(&url, &owned)
+            })
+            .map_err(RankingError::from)
+    }
 
-        let response_text = String::from_utf8_lossy(&output.stdout);
-        match serde_json::from_str::<PeerSpendingResponse>(&response_text) {
-            Ok(response) => Some(response.peers),
-            Err(_) => None,
-        }
+    fn request_peer_spending(url: &str, jwt_token: &str) -> Result<Vec<PeerRecord>, HttpError> {
+        let response: PeerSpendingResponse = http::get_json(url, jwt_token)?;
+        Ok(response.peers)
     }
 
     fn get_ranking_info(&self) -> RankingInfo {
         match self.fetch_ranking_data() {
-            Some(data) => data,
-            None => RankingInfo {
+            Ok(data) => data,
+            Err(err) => RankingInfo {
                 current_rank: None,
                 total_participants: None,
-                status: RankingStatus::Error,
+                status: RankingStatus::Error(err),
             },
         }
     }
 
-    fn get_current_user_spending(&self) -> Option<f64> {
-        // 使用curl命令请求用户信息API
-        let output = Command::new("curl")
-            .args([
-                "-s", // 静默模式
-                "-H", &format!("Authorization: Bearer {}", self.jwt_token),
-                "-H", "Content-Type: application/json",
-                "https://share.packycode.com/api/backend/users/info"
-            ])
-            .output()
-            .ok()?;
-
-        if !output.status.success() {
-            return None;
-        }
-
-        let response_text = String::from_utf8_lossy(&output.stdout);
+    fn get_current_user_spending(&self) -> Result<f64, RankingError> {
+        let token = self
+            .jwt_token()
+            .map_err(|err| RankingError::Token(err.clone()))?;
+        let (url, owned) = (self.endpoints.ranking_user_info.clone(), token.to_string());
+        DiskCache::new(&self.cache, "ranking", CACHE_TTL)
+            .try_get_or_fetch(&self.endpoints.ranking_user_info, token, move || {
+                Self::request_user_spending(&url, &owned)
+            })
+            .map_err(RankingError::from)
+    }
 
-        // 解析JSON响应
-        match serde_json::from_str::<UserInfoResponse>(&response_text) {
-            Ok(response) => {
-                response.daily_spent_usd.parse::<f64>().ok()
-            }
-            Err(_) => None,
-        }
+    fn request_user_spending(url: &str, jwt_token: &str) -> Result<f64, HttpError> {
+        let response: UserInfoResponse = http::get_json(url, jwt_token)?;
+        Ok(response.daily_spent_usd)
     }
 
-    fn fetch_ranking_data(&self) -> Option<RankingInfo> {
-        // 使用curl命令请求排名API
-        let output = Command::new("curl")
-            .args([
-                "-s", // 静默模式
-                "-H", &format!("Authorization: Bearer {}", self.jwt_token),
-                "-H", "Content-Type: application/json",
-                "https://share.packycode.com/api/backend/accounts/peer-spending/today"
-            ])
-            .output()
-            .ok()?;
-
-        if !output.status.success() {
-            return None;
-        }
+    fn fetch_ranking_data(&self) -> Result<RankingInfo, RankingError> {
+        let peers = self.fetch_peer_spending_data()?;
 
-        let response_text = String::from_utf8_lossy(&output.stdout);
+        // 计算排名信息
+        let total_participants = peers.len();
 
-        // 解析JSON响应
-        match serde_json::from_str::<PeerSpendingResponse>(&response_text) {
-            Ok(response) => {
-                // 计算排名信息
-                let total_participants = response.peers.len();
+        if total_participants == 0 {
+            return Ok(RankingInfo {
+                current_rank: None,
+                total_participants: Some(0),
+                status: RankingStatus::Success,
+            });
+        }
 
-                if total_participants == 0 {
-                    return Some(RankingInfo {
-                        current_rank: None,
-                        total_participants: Some(0),
-                        status: RankingStatus::Success,
-                    });
-                }
+        // 解析同行消费数据并排序
+        let mut peer_spending: Vec<(String, f64)> = peers
+            .iter()
+            .filter_map(|peer| {
+                peer.spent_usd_today
+                    .parse::<f64>()
+                    .ok()
+                    .map(|spent| (peer.display_name.clone(), spent))
+            })
+            .collect();
 
-                // 解析同行消费数据并排序
-                let mut peer_spending: Vec<(String, f64)> = response.peers
-                    .iter()
-                    .filter_map(|peer| {
-                        peer.spent_usd_today.parse::<f64>().ok()
-                            .map(|spent| (peer.display_name.clone(), spent))
-                    })
-                    .collect();
-
-                // 按消费金额降序排序
-                peer_spending.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-
-                // 获取当前用户消费数据
-                let current_user_spending = self.get_current_user_spending().unwrap_or(0.0);
-
-                // 计算当前用户排名
-                let mut current_rank = 1;
-                for (_, spent) in &peer_spending {
-                    if current_user_spending >= *spent {
-                        break;
-                    }
-                    current_rank += 1;
-                }
+        // 按消费金额降序排序
+        peer_spending.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
-                // 总参与者数量包括当前用户
-                let total_with_user = total_participants + 1;
+        // 获取当前用户消费数据；拿不到时按 0 计算的排名没有意义
+        let current_user_spending = self.get_current_user_spending()?;
 
-                Some(RankingInfo {
-                    current_rank: Some(current_rank),
-                    total_participants: Some(total_with_user),
-                    status: RankingStatus::Success,
-                })
+        // 计算当前用户排名
+        let mut current_rank = 1;
+        for (_, spent) in &peer_spending {
+            if current_user_spending >= *spent {
+                break;
             }
-            Err(_) => None,
+            current_rank += 1;
         }
-    }
 
-    fn get_first_place_talk(&self) -> &'static str {
-        Self::get_first_place_talk_static()
-    }
+        // 总参与者数量包括当前用户
+        let total_with_user = total_participants + 1;
 
-    fn get_second_place_talk(&self) -> &'static str {
-        Self::get_second_place_talk_static()
-    }
-
-    fn get_third_place_talk(&self) -> &'static str {
-        Self::get_third_place_talk_static()
-    }
-
-    fn get_middle_place_talk(&self) -> &'static str {
-        Self::get_middle_place_talk_static()
-    }
-
-    fn get_last_place_talk(&self) -> &'static str {
-        Self::get_last_place_talk_static()
+        Ok(RankingInfo {
+            current_rank: Some(current_rank),
+            total_participants: Some(total_with_user),
+            status: RankingStatus::Success,
+        })
     }
 
     // 静态版本的垃圾话方法
@@ -269,7 +247,8 @@ impl RankingSegment {
         let index = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
-            .as_secs() as usize % talks.len();
+            .as_secs() as usize
+            % talks.len();
         talks[index]
     }
 
@@ -287,7 +266,8 @@ impl RankingSegment {
         let index = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
-            .as_secs() as usize % talks.len();
+            .as_secs() as usize
+            % talks.len();
         talks[index]
     }
 
@@ -305,7 +285,8 @@ impl RankingSegment {
         let index = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
-            .as_secs() as usize % talks.len();
+            .as_secs() as usize
+            % talks.len();
         talks[index]
     }
 
@@ -323,7 +304,8 @@ impl RankingSegment {
         let index = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
-            .as_secs() as usize % talks.len();
+            .as_secs() as usize
+            % talks.len();
         talks[index]
     }
 
@@ -342,42 +324,84 @@ impl RankingSegment {
         let index = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
-            .as_secs() as usize % talks.len();
+            .as_secs() as usize
+            % talks.len();
         talks[index]
     }
 
-    fn format_ranking_info(&self, info: &RankingInfo) -> String {
-        match info.status {
+    // 排名对应的图标和颜色
+    pub fn rank_icon_and_color(&self, rank: usize) -> (&'static str, Color) {
+        match rank {
+            1 => (self.icons.rank_first, Color::Yellow), // 金色
+            2 => (self.icons.rank_second, Color::White), // 银色
+            3 => (self.icons.rank_third, Color::Red),    // 铜色
+            _ => (self.icons.rank_other, Color::Cyan),   // 青色
+        }
+    }
+
+    fn format_ranking_info(&self, info: &RankingInfo) -> SegmentData {
+        let data = match info.status {
             RankingStatus::Success => {
                 if let (Some(rank), Some(total)) = (info.current_rank, info.total_participants) {
                     if total == 0 {
-                        return "📊 \x1b[36m无排名数据\x1b[0m".to_string();
+                        SegmentData::new("无排名数据")
+                            .with_icon(self.icons.rank_other)
+                            .with_fg(Color::Cyan)
+                    } else {
+                        let (icon, color) = self.rank_icon_and_color(rank);
+                        SegmentData::new(rank.to_string())
+                            .with_icon(icon)
+                            .with_fg(color)
+                            .with_field("rank", rank)
+                            .with_field("total", total)
+                            .with_field("rank_icon", icon)
+                            .with_field("talk", Self::get_trash_talk_by_rank(rank, total))
                     }
-
-                    // 根据排名选择图标和颜色
-                    let (icon, color) = match rank {
-                        1 => ("🥇", "\x1b[33m"), // 金色
-                        2 => ("🥈", "\x1b[37m"), // 银色
-                        3 => ("🥉", "\x1b[31m"), // 铜色
-                        _ => ("📊", "\x1b[36m"), // 青色
-                    };
-
-                    format!("{} {}{}\x1b[0m", icon, color, rank)
                 } else {
-                    "📊 \x1b[36m排名获取中\x1b[0m".to_string()
+                    SegmentData::new("排名获取中")
+                        .with_icon(self.icons.rank_other)
+                        .with_fg(Color::Cyan)
                 }
-            },
-            RankingStatus::Error => {
-                "📊 \x1b[31m排名错误\x1b[0m".to_string()
-            },
-        }
+            }
+            // 其他进程正在请求且没有缓存
+            RankingStatus::Error(RankingError::Busy) => SegmentData::new("排名获取中")
+                .with_icon(self.icons.rank_other)
+                .with_fg(Color::Cyan),
+            RankingStatus::Error(ref err) => {
+                SegmentData::new(format!("排名错误: {}", err.reason()))
+                    .with_icon(self.icons.rank_other)
+                    .with_fg(Color::Red)
+                    .with_state(SegmentState::Warning)
+                    .with_field("reason", err.reason())
+            }
+        };
+        data.with_priority(40)
     }
 }
 
 impl Segment for RankingSegment {
-    fn render(&self, _input: &InputData) -> String {
+    fn id(&self) -> SegmentId {
+        SegmentId::Ranking
+    }
+
+    fn render(&self, _input: &InputData) -> SegmentData {
         if !self.enabled {
-            return String::new();
+            return SegmentData::hidden();
+        }
+        // 没有可用的 token 时停用排名，并说明原因
+        if let Err(TokenError::Expired) = self.jwt_token() {
+            return SegmentData::new("token expired")
+                .with_icon(self.icons.token)
+                .with_fg(Color::Red)
+                .with_priority(40)
+                .with_state(SegmentState::Critical);
+        }
+        if let Err(reason) = self.jwt_token() {
+            return SegmentData::new(format!("排名已停用: {}", reason))
+                .with_icon(self.icons.rank_other)
+                .with_priority(40)
+                .with_state(SegmentState::Warning)
+                .with_field("reason", reason.to_string());
         }
 
         let ranking_info = self.get_ranking_info();
@@ -396,10 +420,47 @@ struct RankingInfo {
     status: RankingStatus,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 enum RankingStatus {
     Success,
-    Error,
+    Error(RankingError),
+}
+
+/// Why the rank could not be fetched
+#[derive(Debug)]
+enum RankingError {
+    Token(TokenError),
+    Http(HttpError),
+    /// Another statusline process is fetching it and nothing is cached
+    Busy,
+}
+
+impl RankingError {
+    // 显示在 "排名错误: " 之后的简短原因
+    fn reason(&self) -> String {
+        match self {
+            RankingError::Token(err) => err.to_string(),
+            RankingError::Http(HttpError::Status(StatusCode::UNAUTHORIZED)) => {
+                "token 已过期".to_string()
+            }
+            RankingError::Http(HttpError::Status(status)) => format!("HTTP {}", status.as_u16()),
+            RankingError::Http(HttpError::Transport(err)) if err.is_timeout() => "超时".to_string(),
+            RankingError::Http(HttpError::Transport(_)) => "连接失败".to_string(),
+            RankingError::Http(HttpError::Decode(_) | HttpError::Field(_)) => {
+                "响应格式错误".to_string()
+            }
+            RankingError::Busy => "排名获取中".to_string(),
+        }
+    }
+}
+
+impl From<FetchError<HttpError>> for RankingError {
+    fn from(err: FetchError<HttpError>) -> Self {
+        match err {
+            FetchError::Busy => RankingError::Busy,
+            FetchError::Failed(err) => RankingError::Http(err),
+        }
+    }
 }
 
 // API响应结构
@@ -411,7 +472,7 @@ struct PeerSpendingResponse {
     timezone: String,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 struct PeerRecord {
     display_name: String,
     spent_usd_today: String,
@@ -421,6 +482,7 @@ struct PeerRecord {
 // 用户信息API响应结构
 #[derive(Deserialize, Serialize)]
 struct UserInfoResponse {
-    daily_spent_usd: String,
+    #[serde(deserialize_with = "crate::core::providers::deserialize_string_to_f64")]
+    daily_spent_usd: f64,
     // 其他字段可以根据需要添加
 }
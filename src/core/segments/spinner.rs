@@ -1,4 +1,5 @@
-use super::Segment;
+use super::text_mode::Icons;
+use super::{Segment, SegmentData, SegmentId};
 use crate::config::InputData;
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -6,40 +7,50 @@ use std::time::{SystemTime, UNIX_EPOCH};
 // 说明：真实刷新由宿主控制；本段仅基于当前时间计算帧索引
 pub struct SpinnerSegment {
     enabled: bool,
-    mode: &'static str, // 目前简单用常量模式，可后续接入配置
+    mode: &'static str, // 帧集合，由图标模式决定
 }
 
 impl SpinnerSegment {
     pub fn new(enabled: bool) -> Self {
-        Self { enabled, mode: "kaomoji" }
+        Self {
+            enabled,
+            mode: "kaomoji",
+        }
+    }
+
+    pub fn with_icons(mut self, icons: Icons) -> Self {
+        self.mode = icons.spinner;
+        self
     }
 
     fn frames(&self) -> &'static [&'static str] {
         match self.mode {
             // 热门颜文字动画
             "kaomoji" => &[
-                "(｡◕‿◕｡)",   // 开心
+                "(｡◕‿◕｡)",      // 开心
                 "(＾◡＾)",      // 愉快
-                "(´∀｀)",      // 高兴
-                "\\(^o^)/",    // 万岁
-                "(≧∇≦)",      // 兴奋
-                "(◕‿◕)",      // 微笑
-                "(╯✧▽✧)╯",   // 欢呼
+                "(´∀｀)",       // 高兴
+                "\\(^o^)/",     // 万岁
+                "(≧∇≦)",        // 兴奋
+                "(◕‿◕)",        // 微笑
+                "(╯✧▽✧)╯",      // 欢呼
                 "(ﾉ◕ヮ◕)ﾉ*:･ﾟ✧", // 庆祝
-                "(*^▽^*)",    // 害羞开心
-                "(◉‿◉)",      // 眯眼笑
-                "(*´∀`)~♥",   // 心
-                "( ´•̥̥̥ω•̥̥̥` )", // 惊讶
+                "(*^▽^*)",      // 害羞开心
+                "(◉‿◉)",        // 眯眼笑
+                "(*´∀`)~♥",     // 心
+                "( ´•̥̥̥ω•̥̥̥` )",    // 惊讶
                 "(●´ω｀●)ゞ",
                 "_(:3 ⌒ﾞ)_",
                 "_:(´□`」 ∠):_",
                 "▼・ᴥ・▼",
                 "ʕ´• ᴥ•̥`ʔ",
-                "(›´ω`‹ )"
+                "(›´ω`‹ )",
             ],
+            // 纯 ASCII 旋转线
+            "ascii" => &["|", "/", "-", "\\"],
             _ => &[
-                "(｡◕‿◕｡)",   // 默认也使用颜文字
-                "(＾◡＾)", 
+                "(｡◕‿◕｡)", // 默认也使用颜文字
+                "(＾◡＾)",
                 "(´∀｀)",
                 "\\(^o^)/",
             ],
@@ -48,17 +59,30 @@ impl SpinnerSegment {
 }
 
 impl Segment for SpinnerSegment {
-    fn render(&self, _input: &InputData) -> String {
-        if !self.enabled { return String::new(); }
+    fn id(&self) -> SegmentId {
+        SegmentId::Spinner
+    }
+
+    fn render(&self, _input: &InputData) -> SegmentData {
+        if !self.enabled {
+            return SegmentData::hidden();
+        }
         let frames = self.frames();
-        if frames.is_empty() { return String::new(); }
+        if frames.is_empty() {
+            return SegmentData::hidden();
+        }
         // 以时间片选择帧：500ms 一帧（稍慢一些让颜文字更易读）
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
         let bucket = (now.as_millis() / 500) as usize;
         let idx = bucket % frames.len();
-        frames[idx].to_string()
+        SegmentData::new(frames[idx])
+            .with_priority(10)
+            .with_field("frame", frames[idx])
     }
 
-    fn enabled(&self) -> bool { self.enabled }
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
 }
-
@@ -1,16 +1,75 @@
 // 纯文本模式的图标定义
+#[derive(Debug, Clone, Copy)]
 pub struct Icons {
     pub model: &'static str,
     pub directory: &'static str,
     pub git: &'static str,
     pub usage: &'static str,
+    // 今日花费档位：低 / 中 / 高 / 很高，以及获取失败
+    pub quota_low: &'static str,
+    pub quota_medium: &'static str,
+    pub quota_high: &'static str,
+    pub quota_critical: &'static str,
+    pub quota_unknown: &'static str,
+    // 本月花费，以及快于 / 慢于匀速花费的标记
+    pub monthly: &'static str,
+    pub pace_over: &'static str,
+    pub pace_under: &'static str,
+    // 排名奖牌
+    pub rank_first: &'static str,
+    pub rank_second: &'static str,
+    pub rank_third: &'static str,
+    pub rank_other: &'static str,
+    // JWT 即将过期 / 已过期
+    pub token: &'static str,
+    // 网络延迟：低 / 中 / 高 / 未知
+    pub network_good: &'static str,
+    pub network_warn: &'static str,
+    pub network_bad: &'static str,
+    pub network_unknown: &'static str,
+    pub time: &'static str,
+    // Spinner 帧集合名称，见 SpinnerSegment::frames
+    pub spinner: &'static str,
+    // Git 状态符号
+    pub git_clean: &'static str,
+    pub git_dirty: &'static str,
+    pub git_conflicts: &'static str,
+    pub git_ahead: &'static str,
+    pub git_behind: &'static str,
+    // 段落未在时限内完成时附加的标记
+    pub stale: &'static str,
 }
 
 pub const NERD_ICONS: Icons = Icons {
-    model: "\u{e26d}",      // 
-    directory: "\u{f024b}",  // 󰉋
-    git: "\u{f02a2}",        // 󰊢
-    usage: "\u{f49b}",       // 
+    model: "\u{e26d}",
+    directory: "\u{f024b}", // 󰉋
+    git: "\u{f02a2}",       // 󰊢
+    usage: "\u{f49b}",
+    quota_low: "\u{f155}",
+    quota_medium: "\u{f155}",
+    quota_high: "\u{f155}",
+    quota_critical: "\u{f155}",
+    quota_unknown: "\u{f128}",
+    monthly: "\u{f073}",
+    pace_over: "▲",
+    pace_under: "▼",
+    rank_first: "\u{f091}",
+    rank_second: "\u{f091}",
+    rank_third: "\u{f091}",
+    rank_other: "\u{f080}",
+    token: "\u{f084}",
+    network_good: "\u{f1eb}",
+    network_warn: "\u{f1eb}",
+    network_bad: "\u{f1eb}",
+    network_unknown: "\u{f1eb}",
+    time: "\u{f017}",
+    spinner: "kaomoji",
+    git_clean: "\u{f00c}",
+    git_dirty: "\u{f111}",
+    git_conflicts: "\u{f071}",
+    git_ahead: "\u{f062}",
+    git_behind: "\u{f063}",
+    stale: "\u{f252}",
 };
 
 pub const EMOJI_ICONS: Icons = Icons {
@@ -18,6 +77,31 @@ pub const EMOJI_ICONS: Icons = Icons {
     directory: "📁",
     git: "🌿",
     usage: "📊",
+    quota_low: "💚",
+    quota_medium: "💛",
+    quota_high: "🧡",
+    quota_critical: "❤️",
+    quota_unknown: "◔",
+    monthly: "📅",
+    pace_over: "▲",
+    pace_under: "▼",
+    rank_first: "🥇",
+    rank_second: "🥈",
+    rank_third: "🥉",
+    rank_other: "📊",
+    token: "🔑",
+    network_good: "🟩",
+    network_warn: "🟨",
+    network_bad: "🟥",
+    network_unknown: "🟦",
+    time: "🕒",
+    spinner: "kaomoji",
+    git_clean: "✓",
+    git_dirty: "●",
+    git_conflicts: "⚠",
+    git_ahead: "↑",
+    git_behind: "↓",
+    stale: "⏳",
 };
 
 pub const ASCII_ICONS: Icons = Icons {
@@ -25,13 +109,64 @@ pub const ASCII_ICONS: Icons = Icons {
     directory: "[D]",
     git: "[G]",
     usage: "[U]",
+    quota_low: "$",
+    quota_medium: "$",
+    quota_high: "$$",
+    quota_critical: "$$$",
+    quota_unknown: "$?",
+    monthly: "[Mo]",
+    pace_over: "^",
+    pace_under: "v",
+    rank_first: "#",
+    rank_second: "#",
+    rank_third: "#",
+    rank_other: "#",
+    token: "[K]",
+    network_good: "[N]",
+    network_warn: "[N]",
+    network_bad: "[N!]",
+    network_unknown: "[N?]",
+    time: "[T]",
+    spinner: "ascii",
+    git_clean: "ok",
+    git_dirty: "*",
+    git_conflicts: "!",
+    git_ahead: "^",
+    git_behind: "v",
+    stale: "~",
 };
 
+// 无图标模式：Git 状态与花费速度符号承载信息，保留 ASCII 版本
 pub const NO_ICONS: Icons = Icons {
     model: "",
     directory: "",
     git: "",
     usage: "",
+    quota_low: "",
+    quota_medium: "",
+    quota_high: "",
+    quota_critical: "",
+    quota_unknown: "",
+    monthly: "",
+    pace_over: "^",
+    pace_under: "v",
+    rank_first: "",
+    rank_second: "",
+    rank_third: "",
+    rank_other: "",
+    token: "",
+    network_good: "",
+    network_warn: "",
+    network_bad: "",
+    network_unknown: "",
+    time: "",
+    spinner: "ascii",
+    git_clean: "ok",
+    git_dirty: "*",
+    git_conflicts: "!",
+    git_ahead: "^",
+    git_behind: "v",
+    stale: "~",
 };
 
 pub fn get_icons(mode: &str) -> Icons {
@@ -41,4 +176,4 @@ pub fn get_icons(mode: &str) -> Icons {
         "none" => NO_ICONS,
         _ => NERD_ICONS,
     }
-}
\ No newline at end of file
+}
@@ -1,31 +1,47 @@
-use super::Segment;
+use super::text_mode::{Icons, EMOJI_ICONS};
+use super::{Segment, SegmentData, SegmentId};
 use crate::config::InputData;
 use chrono::Local;
 
 // 时间段：显示当前本地时间，格式 HH:mm:ss
 pub struct TimeSegment {
     enabled: bool,
+    icons: Icons,
 }
 
 impl TimeSegment {
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
+        Self {
+            enabled,
+            icons: EMOJI_ICONS,
+        }
+    }
+
+    pub fn with_icons(mut self, icons: Icons) -> Self {
+        self.icons = icons;
+        self
     }
 }
 
 impl Segment for TimeSegment {
-    fn render(&self, _input: &InputData) -> String {
+    fn id(&self) -> SegmentId {
+        SegmentId::Time
+    }
+
+    fn render(&self, _input: &InputData) -> SegmentData {
         if !self.enabled {
-            return String::new();
+            return SegmentData::hidden();
         }
         let now = Local::now();
         // 使用 24 小时制 HH:mm:ss
         let time_str = now.format("%H:%M:%S").to_string();
-        format!("{} {}", "\u{1F552}", time_str) // 🕒 HH:mm:ss
+        SegmentData::new(time_str.clone())
+            .with_icon(self.icons.time)
+            .with_priority(20)
+            .with_field("time", time_str)
     }
 
     fn enabled(&self) -> bool {
         self.enabled
     }
 }
-
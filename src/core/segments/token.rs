@@ -0,0 +1,80 @@
+use super::text_mode::{Icons, EMOJI_ICONS};
+use super::{Segment, SegmentData, SegmentId, SegmentState};
+use crate::config::token::format_remaining;
+use crate::config::{InputData, JwtClaims, SharedJwtToken};
+use crate::core::style::Color;
+use chrono::Utc;
+
+// JWT 过期提醒：临近过期时显示 "expires in 2d"，过期后显示 "token expired"
+pub struct TokenSegment {
+    enabled: bool,
+    token: SharedJwtToken,
+    icons: Icons,
+    warning_days: u64,
+}
+
+impl TokenSegment {
+    pub fn new(enabled: bool, token: SharedJwtToken) -> Self {
+        Self {
+            enabled,
+            token,
+            icons: EMOJI_ICONS,
+            warning_days: 3,
+        }
+    }
+
+    pub fn with_icons(mut self, icons: Icons) -> Self {
+        self.icons = icons;
+        self
+    }
+
+    /// Start warning this many days before expiry
+    pub fn with_warning_days(mut self, days: u64) -> Self {
+        self.warning_days = days;
+        self
+    }
+}
+
+impl Segment for TokenSegment {
+    fn id(&self) -> SegmentId {
+        SegmentId::Token
+    }
+
+    fn render(&self, _input: &InputData) -> SegmentData {
+        if !self.enabled {
+            return SegmentData::hidden();
+        }
+        // 没有 token 时由 quota 段落的排名位置说明原因，这里不重复提示
+        let Some(claims) = self
+            .token
+            .resolve()
+            .ok()
+            .and_then(|token| JwtClaims::decode(&token))
+        else {
+            return SegmentData::hidden();
+        };
+        let Some(remaining) = claims.expires_in(Utc::now().timestamp()) else {
+            return SegmentData::hidden();
+        };
+
+        let data = if remaining <= 0 {
+            SegmentData::new("token expired")
+                .with_fg(Color::Red)
+                .with_state(SegmentState::Critical)
+        } else if remaining < self.warning_days as i64 * 86400 {
+            SegmentData::new(format!("expires in {}", format_remaining(remaining)))
+                .with_fg(Color::Yellow)
+                .with_state(SegmentState::Warning)
+        } else {
+            return SegmentData::hidden();
+        };
+        data.with_icon(self.icons.token)
+            .with_priority(60)
+            .with_field("expires_in", format_remaining(remaining))
+            .with_field("username", claims.username.unwrap_or_default())
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+}
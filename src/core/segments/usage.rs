@@ -1,25 +1,52 @@
-use super::Segment;
-use crate::config::{InputData, TranscriptEntry};
+use super::text_mode::{Icons, EMOJI_ICONS};
+use super::{Segment, SegmentData, SegmentId, SegmentState};
+use crate::config::{BarsConfig, InputData, TranscriptEntry};
+use crate::core::bar;
 use std::fs;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 
 const CONTEXT_LIMIT: u32 = 200000;
+// 上下文占用达到这些百分比时分别视为 warning / critical
+const WARNING_PERCENT: f64 = 60.0;
+const CRITICAL_PERCENT: f64 = 85.0;
 
 pub struct UsageSegment {
     enabled: bool,
+    icons: Icons,
+    bar: Option<BarsConfig>,
 }
 
 impl UsageSegment {
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
+        Self {
+            enabled,
+            icons: EMOJI_ICONS,
+            bar: None,
+        }
+    }
+
+    pub fn with_icons(mut self, icons: Icons) -> Self {
+        self.icons = icons;
+        self
+    }
+
+    /// Show a context-window bar in front of the percentage
+    pub fn with_bar(mut self, bar: Option<BarsConfig>) -> Self {
+        self.bar = bar;
+        self
     }
 }
 
 impl Segment for UsageSegment {
-    fn render(&self, input: &InputData) -> String {
-        if !self.enabled {
-            return String::new();
+    fn id(&self) -> SegmentId {
+        SegmentId::Usage
+    }
+
+    fn render(&self, input: &InputData) -> SegmentData {
+        // 非 Claude Code 调用时没有 transcript
+        if !self.enabled || input.transcript_path.is_empty() {
+            return SegmentData::hidden();
         }
 
         let context_used_token = parse_transcript_usage(&input.transcript_path);
@@ -30,7 +57,40 @@ impl Segment for UsageSegment {
             context_used_token.to_string()
         };
 
-        format!("📊 {:.1}% · {} tokens", context_used_rate, tokens_display)
+        let mut text = format!("{:.1}% · {} tokens", context_used_rate, tokens_display);
+        // {bar} 字段始终可用；默认文本只在启用 bars.usage 时带进度条
+        let bar_text = bar::render_bar(
+            context_used_rate / 100.0,
+            self.bar.as_ref().unwrap_or(&BarsConfig::default()),
+        );
+        if self.bar.is_some() {
+            text = format!("{} {}", bar_text, text);
+        }
+
+        let state = if context_used_rate >= CRITICAL_PERCENT {
+            SegmentState::Critical
+        } else if context_used_rate >= WARNING_PERCENT {
+            SegmentState::Warning
+        } else {
+            SegmentState::Normal
+        };
+
+        let mut data = SegmentData::new(text)
+            .with_icon(self.icons.usage)
+            .with_priority(60)
+            .with_state(state)
+            .with_field("percent", context_used_rate)
+            .with_field("tokens", context_used_token)
+            .with_field("limit", CONTEXT_LIMIT)
+            .with_field("bar", bar_text);
+        if let Some(color) = self
+            .bar
+            .as_ref()
+            .and_then(|config| bar::stop_color(context_used_rate, config))
+        {
+            data = data.with_fg(color);
+        }
+        data
     }
 
     fn enabled(&self) -> bool {
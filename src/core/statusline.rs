@@ -1,90 +1,643 @@
-use crate::config::{Config, InputData};
+use crate::config::{
+    ApiSettings, Config, InputData, JwtTokenSources, PackyCodeEndpoints, RowConfig, SharedJwtToken,
+};
+use crate::core::cache::{self, MemoryCache};
+use crate::core::link::{hyperlink, hyperlinks_enabled};
+use crate::core::output::{self, OutputFormat, RenderedSegment};
 use crate::core::segments::{
-    DirectorySegment, GitSegment, ModelSegment, QuotaSegment, Segment, UsageSegment, SpinnerSegment, NetworkSegment, RankingSegment,
+    get_icons, DirectorySegment, EmojiSegment, GitSegment, Icons, ModelSegment, MonthlySegment,
+    NetworkSegment, QuotaSegment, RankingSegment, Segment, SegmentData, SegmentId, SpinnerSegment,
+    TimeSegment, TokenSegment, UsageSegment,
 };
+use crate::core::style::{Color, ColorMode, Style};
+use crate::core::template::Template;
+use crate::core::terminal::TerminalEnv;
+use crate::core::theme::Theme;
+use crate::core::width::{display_width, truncate_to_width};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// 截断后文本至少保留的宽度，再短就直接隐藏该段落
+const MIN_SHORTENED_WIDTH: usize = 6;
+
+// 各段落最近一次的渲染结果，段落超时时显示
+static LAST_RENDERED: MemoryCache<SegmentData> = MemoryCache::new(Duration::from_secs(3600));
+
+// 与目录相关的段落（git 等）不能沿用其他目录的结果
+fn last_rendered_key(id: SegmentId, input: &InputData) -> String {
+    format!("{}|{}", id.name(), input.workspace.current_dir)
+}
+
+/// One output line resolved from `[layout]`
+struct Row {
+    segments: Vec<SegmentId>,
+    separator: String,
+    separator_color: String,
+    align: String,
+}
 
 pub struct StatusLineGenerator {
     config: Config,
+    theme: Theme,
+    icons: Icons,
+    color_mode: ColorMode,
+    hyperlinks: bool,
+    format: OutputFormat,
+    terminal: TerminalEnv,
 }
 
 impl StatusLineGenerator {
     pub fn new(config: Config) -> Self {
-        Self { config }
+        Self::new_with_terminal(config, TerminalEnv::current())
+    }
+
+    /// Render for a terminal other than our own, e.g. a daemon client's
+    pub fn new_with_terminal(config: Config, terminal: TerminalEnv) -> Self {
+        let theme = Theme::from_config(&config);
+        let icons = get_icons(&config.icons);
+        let color_mode = ColorMode::from_config(&config.color_mode, &terminal);
+        let hyperlinks = hyperlinks_enabled(&config.hyperlinks, &terminal);
+        Self {
+            config,
+            theme,
+            icons,
+            color_mode,
+            hyperlinks,
+            format: OutputFormat::Ansi,
+            terminal,
+        }
+    }
+
+    /// Output format; everything except `ansi` drops escape sequences and links
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        if format != OutputFormat::Ansi {
+            self.hyperlinks = false;
+        }
+        if format == OutputFormat::Plain {
+            self.color_mode = ColorMode::None;
+        }
+        self
     }
 
     pub fn generate(&self, input: &InputData) -> String {
-        // 正常的statusline生成，不包含测速信息
-        self.generate_normal_statusline(input)
+        let rows = self.rows();
+        let ids: Vec<SegmentId> = rows.iter().flat_map(|row| row.segments.clone()).collect();
+        let rendered = self.render_segments(&ids, input);
+
+        match self.format {
+            OutputFormat::Json => output::to_json(&self.rendered_segments(&rows, &rendered)),
+            OutputFormat::Waybar => output::to_waybar(
+                &self.rendered_segments(&rows, &rendered),
+                &self.config.layout.separator,
+            ),
+            OutputFormat::I3bar => output::to_i3bar(&self.rendered_segments(&rows, &rendered)),
+            // 正常的statusline生成，不包含测速信息
+            _ => self.generate_normal_statusline(&rows, &rendered),
+        }
+    }
+
+    /// Render the segments concurrently, each in its own thread. A segment
+    /// still running at its timeout (or the overall deadline) is shown with
+    /// its last known value marked stale, or as a bare stale marker; its
+    /// thread keeps running and fills the caches for the next render, and
+    /// `cache::wait_for_revalidation` waits for it before the process exits.
+    fn render_segments(
+        &self,
+        ids: &[SegmentId],
+        input: &InputData,
+    ) -> HashMap<SegmentId, SegmentData> {
+        let start = Instant::now();
+        let input = Arc::new(input.clone());
+        // daemon 后台刷新时，工作线程同样绕过缓存
+        let refreshing = cache::is_refreshing();
+        let (sender, receiver) = mpsc::channel();
+        // token、ranking 与 quota 内嵌的排名共用一次解析结果
+        let jwt_token = SharedJwtToken::new(JwtTokenSources::from_config(&self.config));
+
+        for &id in ids {
+            let segment = self.create_segment(id, &jwt_token);
+            let input = Arc::clone(&input);
+            let sender = sender.clone();
+            let pending = cache::Pending::begin();
+            thread::spawn(move || {
+                let data = if refreshing {
+                    cache::refreshing(|| segment.render(&input))
+                } else {
+                    segment.render(&input)
+                };
+                if data.is_visible() {
+                    LAST_RENDERED.set(&last_rendered_key(id, &input), data.clone());
+                }
+                let _ = sender.send((id, data));
+                drop(pending);
+            });
+        }
+        drop(sender);
+
+        let mut results: HashMap<SegmentId, SegmentData> = HashMap::new();
+        loop {
+            let pending: Vec<SegmentId> = ids
+                .iter()
+                .copied()
+                .filter(|id| !results.contains_key(id))
+                .collect();
+            if pending.is_empty() {
+                break;
+            }
+
+            // 等到最早到期的段落；全部没有时限时一直等待
+            let next_timeout = pending
+                .iter()
+                .filter_map(|id| self.segment_timeout(*id))
+                .min();
+            let received = match next_timeout {
+                Some(timeout) => receiver.recv_timeout(timeout.saturating_sub(start.elapsed())),
+                None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match received {
+                Ok((id, data)) => {
+                    results.insert(id, data);
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    let elapsed = start.elapsed();
+                    for id in pending {
+                        if self
+                            .segment_timeout(id)
+                            .is_some_and(|timeout| timeout <= elapsed)
+                        {
+                            results.insert(id, self.stale_data(id, &input));
+                        }
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        results
+    }
+
+    /// `[timing]` limit for a segment: its own timeout capped by the deadline
+    fn segment_timeout(&self, id: SegmentId) -> Option<Duration> {
+        let timing = &self.config.timing;
+        let deadline = Some(timing.deadline_ms).filter(|ms| *ms > 0);
+        let own = timing.segment_timeouts.get(id.name()).copied();
+        let limit = match (deadline, own) {
+            (Some(deadline), Some(own)) => Some(deadline.min(own)),
+            (deadline, own) => deadline.or(own),
+        };
+        limit.map(Duration::from_millis)
     }
 
-    fn generate_normal_statusline(&self, input: &InputData) -> String {
-        let mut segments: Vec<String> = Vec::new();
+    fn stale_data(&self, id: SegmentId, input: &InputData) -> SegmentData {
+        let mut data = LAST_RENDERED
+            .get(&last_rendered_key(id, input))
+            .unwrap_or_default();
+        data.stale = true;
+        data
+    }
 
-        // Assemble segments with proper colors
-        // Spinner at the very beginning (bright bold green like directory + white background + single space padding)
-        if self.config.segments.spinner {
-            let spinner = SpinnerSegment::new(true);
-            let frame = spinner.render(input);
-            if !frame.is_empty() {
-                segments.push(format!("\x1b[1;32;47m {} \x1b[0m", frame)); // 粗体亮绿色+白背景+前后各1个空格
+    /// Visible segments of every row with their theme styles. The consumer
+    /// (a desktop bar or script) decides on width, so nothing is truncated.
+    fn rendered_segments(
+        &self,
+        rows: &[Row],
+        rendered: &HashMap<SegmentId, SegmentData>,
+    ) -> Vec<RenderedSegment> {
+        let powerline = self.config.layout.mode == "powerline";
+        let mut segments = Vec::new();
+        for (index, row) in rows.iter().enumerate() {
+            for (id, data) in self.collect_row(row, rendered, None) {
+                let style = if powerline {
+                    self.powerline_style(id, &data)
+                } else {
+                    self.segment_style(id, &data)
+                };
+                segments.push(RenderedSegment {
+                    row: index,
+                    id,
+                    data,
+                    style,
+                });
             }
         }
+        segments
+    }
 
-        if self.config.segments.model {
-            let model_segment = ModelSegment::new(true);
-            let content = model_segment.render(input);
-            segments.push(format!("\x1b[1;36m{}\x1b[0m", content));
+    /// Resolve the rows to render. Without `[[layout.rows]]` there is a
+    /// single row in `order`. Names are used once (duplicates and unknown
+    /// names ignored); enabled segments missing from every list are appended
+    /// to the last row in the default order.
+    fn rows(&self) -> Vec<Row> {
+        let layout = &self.config.layout;
+        let single_row;
+        let row_configs: &[RowConfig] = if layout.rows.is_empty() {
+            single_row = [RowConfig {
+                segments: layout.order.clone(),
+                ..RowConfig::default()
+            }];
+            &single_row
+        } else {
+            &layout.rows
+        };
+
+        let mut seen: Vec<SegmentId> = Vec::new();
+        let mut rows: Vec<Row> = row_configs
+            .iter()
+            .map(|config| {
+                let mut segments = Vec::new();
+                for id in config
+                    .segments
+                    .iter()
+                    .filter_map(|name| SegmentId::from_name(name))
+                {
+                    if !seen.contains(&id) {
+                        seen.push(id);
+                        segments.push(id);
+                    }
+                }
+                Row {
+                    segments,
+                    separator: config
+                        .separator
+                        .clone()
+                        .unwrap_or_else(|| layout.separator.clone()),
+                    separator_color: config
+                        .separator_color
+                        .clone()
+                        .unwrap_or_else(|| layout.separator_color.clone()),
+                    align: config.align.clone(),
+                }
+            })
+            .collect();
+
+        if let Some(last) = rows.last_mut() {
+            for id in SegmentId::DEFAULT_ORDER {
+                if !seen.contains(&id) {
+                    last.segments.push(id);
+                }
+            }
         }
+        for row in &mut rows {
+            row.segments.retain(|id| self.segment_enabled(*id));
+        }
+        rows
+    }
 
-        if self.config.segments.directory {
-            let dir_segment = DirectorySegment::new(true);
-            let dir_name = dir_segment.render(input);
-            // 使用 Emoji 图标 + 绿字目录名
-            segments.push(format!("\x1b[1;33m📁\x1b[0m \x1b[1;32m{}\x1b[0m", dir_name));
+    fn segment_enabled(&self, id: SegmentId) -> bool {
+        let enabled = &self.config.segments;
+        match id {
+            SegmentId::Spinner => enabled.spinner,
+            SegmentId::Model => enabled.model,
+            SegmentId::Directory => enabled.directory,
+            SegmentId::Git => enabled.git,
+            SegmentId::Usage => enabled.usage,
+            SegmentId::Quota => enabled.quota,
+            SegmentId::Monthly => enabled.monthly,
+            SegmentId::Network => enabled.network,
+            SegmentId::Time => enabled.time,
+            SegmentId::Emoji => enabled.emoji,
+            SegmentId::Ranking => enabled.ranking,
+            SegmentId::Token => enabled.token,
         }
+    }
 
-        if self.config.segments.git {
-            let git_segment = GitSegment::new(true);
-            let git_output = git_segment.render(input);
-            if !git_output.is_empty() {
-                segments.push(format!("\x1b[1;34m{}\x1b[0m", git_output));
+    /// `[packycode]` endpoints for the API base URL Claude Code uses
+    fn packycode_endpoints(&self) -> PackyCodeEndpoints {
+        let settings = ApiSettings::load();
+        self.config
+            .packycode
+            .endpoints(&settings.base_url, settings.info_url.as_deref())
+    }
+
+    fn quota_segment(&self, jwt_token: &SharedJwtToken) -> QuotaSegment {
+        QuotaSegment::new_with_config(true, jwt_token.clone())
+            .with_icons(self.icons)
+            .with_cache(self.config.cache.clone())
+            .with_packycode(&self.config.packycode)
+            .with_quota(&self.config.quota)
+    }
+
+    fn create_segment(&self, id: SegmentId, jwt_token: &SharedJwtToken) -> Box<dyn Segment> {
+        let icons = self.icons;
+        let bars = &self.config.bars;
+        match id {
+            SegmentId::Spinner => Box::new(SpinnerSegment::new(true).with_icons(icons)),
+            SegmentId::Model => Box::new(ModelSegment::new(true).with_icons(icons)),
+            SegmentId::Directory => Box::new(DirectorySegment::new(true).with_icons(icons)),
+            SegmentId::Git => Box::new(
+                GitSegment::new(true)
+                    .with_icons(icons)
+                    .with_link(self.hyperlinks || self.format == OutputFormat::Json),
+            ),
+            SegmentId::Usage => Box::new(
+                UsageSegment::new(true)
+                    .with_icons(icons)
+                    .with_bar(bars.usage.then(|| bars.clone())),
+            ),
+            SegmentId::Quota => Box::new(
+                self.quota_segment(jwt_token)
+                    .with_bar(bars.quota.then(|| bars.clone())),
+            ),
+            // 与 quota 共用服务商与缓存，不额外请求
+            SegmentId::Monthly => {
+                Box::new(MonthlySegment::new(true, self.quota_segment(jwt_token)).with_icons(icons))
             }
+            SegmentId::Network => Box::new(
+                NetworkSegment::new(true)
+                    .with_icons(icons)
+                    .with_cache(self.config.cache.clone()),
+            ),
+            SegmentId::Time => Box::new(TimeSegment::new(true).with_icons(icons)),
+            // 末尾表情：每 2 秒切换一枚（基于当前时间计算）
+            SegmentId::Emoji => Box::new(EmojiSegment::new(true)),
+            // 排名默认集成在 quota 中，仅在 layout.order 中显式列出时单独显示
+            SegmentId::Ranking => Box::new(
+                RankingSegment::new_with_token(true, jwt_token.clone())
+                    .with_icons(icons)
+                    .with_cache(self.config.cache.clone())
+                    .with_endpoints(self.packycode_endpoints()),
+            ),
+            SegmentId::Token => Box::new(
+                TokenSegment::new(true, jwt_token.clone())
+                    .with_icons(icons)
+                    .with_warning_days(self.config.jwt_expiry_warning_days),
+            ),
         }
+    }
 
-        if self.config.segments.usage {
-            let usage_segment = UsageSegment::new(true);
-            let content = usage_segment.render(input);
-            segments.push(format!("\x1b[1;35m{}\x1b[0m", content));
+    fn generate_normal_statusline(
+        &self,
+        rows: &[Row],
+        rendered: &HashMap<SegmentId, SegmentData>,
+    ) -> String {
+        let max_width = self.max_width();
+        let lines: Vec<String> = rows
+            .iter()
+            .map(|row| self.render_row(row, rendered, max_width))
+            .filter(|line| !line.is_empty())
+            .collect();
+        lines.join("\n")
+    }
+
+    fn render_row(
+        &self,
+        row: &Row,
+        rendered: &HashMap<SegmentId, SegmentData>,
+        max_width: Option<usize>,
+    ) -> String {
+        let segments = self.collect_row(row, rendered, max_width);
+        let line = match self.config.layout.mode.as_str() {
+            "powerline" => self.render_powerline(&segments),
+            _ => self.render_plain(&segments, row),
+        };
+        self.align(line, self.line_width(&segments, row), row, max_width)
+    }
+
+    /// Format and fit the segments of one row; hidden ones are dropped
+    fn collect_row(
+        &self,
+        row: &Row,
+        rendered: &HashMap<SegmentId, SegmentData>,
+        max_width: Option<usize>,
+    ) -> Vec<(SegmentId, SegmentData)> {
+        let mut segments: Vec<(SegmentId, SegmentData)> = row
+            .segments
+            .iter()
+            .filter_map(|id| Some((*id, rendered.get(id)?.clone())))
+            .map(|(id, data)| (id, self.mark_stale(self.apply_format(id, data))))
+            .filter(|(_, data)| data.is_visible())
+            .collect();
+
+        if let Some(max_width) = max_width {
+            self.fit_to_width(&mut segments, row, max_width);
         }
+        segments
+    }
 
-        if self.config.segments.quota {
-            let quota_segment = QuotaSegment::new_with_config(true, self.config.jwt_token.clone());
-            let content = quota_segment.render(input);
-            if !content.is_empty() {
-                segments.push(format!("\x1b[1;93m{}\x1b[0m", content));
+    /// Replace the segment text with its `[formats]` template, if configured.
+    /// Segments without fields (hidden or failed) keep their text.
+    fn apply_format(&self, id: SegmentId, mut data: SegmentData) -> SegmentData {
+        if let Some(format) = self.config.formats.get(id.name()) {
+            if !data.hidden && !data.fields.is_empty() {
+                data.text = Template::parse(format).render(&data.fields);
             }
         }
+        data
+    }
+
+    // 过期的段落在文本后附加标记；没有旧值时只显示标记
+    fn mark_stale(&self, mut data: SegmentData) -> SegmentData {
+        if data.stale {
+            data.text = if data.text.is_empty() {
+                self.icons.stale.to_string()
+            } else {
+                format!("{} {}", data.text, self.icons.stale)
+            };
+        }
+        data
+    }
+
+    // 居中/右对齐：在行首补空格，宽度未知时保持左对齐
+    // 宽度由段落计算，不受 tmux 标记等非显示字符影响
+    fn align(&self, line: String, width: usize, row: &Row, max_width: Option<usize>) -> String {
+        let Some(max_width) = max_width else {
+            return line;
+        };
+        if line.is_empty() {
+            return line;
+        }
+        let free = max_width.saturating_sub(width);
+        let indent = match row.align.as_str() {
+            "right" => free,
+            "center" => free / 2,
+            _ => 0,
+        };
+        format!("{}{}", " ".repeat(indent), line)
+    }
+
+    fn max_width(&self) -> Option<usize> {
+        match self.config.layout.max_width {
+            0 => self.terminal.width,
+            width => Some(width),
+        }
+    }
+
+    /// Shorten or drop the lowest-priority segments (rightmost first on ties)
+    /// until the line fits the available width.
+    fn fit_to_width(
+        &self,
+        segments: &mut Vec<(SegmentId, SegmentData)>,
+        row: &Row,
+        max_width: usize,
+    ) {
+        let mut candidates: Vec<usize> = (0..segments.len()).collect();
+        candidates.sort_by_key(|&i| (segments[i].1.priority, std::cmp::Reverse(i)));
 
-        if self.config.segments.network {
-            let network_segment = NetworkSegment::new(true);
-            let content = network_segment.render(input);
-            if !content.is_empty() {
-                segments.push(format!("\x1b[1;94m{}\x1b[0m", content)); // 亮蓝色
+        for i in candidates {
+            let overflow = self.line_width(segments, row).saturating_sub(max_width);
+            if overflow == 0 {
+                break;
+            }
+            let data = &mut segments[i].1;
+            let text_width = display_width(&data.text);
+            if text_width >= overflow + MIN_SHORTENED_WIDTH {
+                data.text = truncate_to_width(&data.text, text_width - overflow);
+            } else {
+                data.hidden = true;
             }
         }
 
-        // 排名功能已集成到quota segment中，不再单独显示
+        segments.retain(|(_, data)| data.is_visible());
+    }
+
+    /// Display width of the line the visible segments would produce
+    fn line_width(&self, segments: &[(SegmentId, SegmentData)], row: &Row) -> usize {
+        let powerline = self.config.layout.mode == "powerline";
+        let visible: Vec<&(SegmentId, SegmentData)> = segments
+            .iter()
+            .filter(|(_, data)| data.is_visible())
+            .collect();
+
+        let content: usize = visible
+            .iter()
+            .map(|(id, data)| {
+                let mut padding = self.padding_for(*id);
+                if powerline || self.segment_style(*id, data).bg.is_some() {
+                    padding = padding.max(1);
+                }
+                display_width(&data.content()) + padding * 2
+            })
+            .sum();
+
+        let joints = if powerline {
+            // 每个段落后都有一个箭头（plain 样式除外）
+            let glyph = if self.config.layout.powerline_style == "plain" {
+                0
+            } else {
+                1
+            };
+            visible.len() * glyph
+        } else {
+            visible.len().saturating_sub(1) * display_width(&row.separator)
+        };
+        content + joints
+    }
+
+    fn render_plain(&self, segments: &[(SegmentId, SegmentData)], row: &Row) -> String {
+        let rendered: Vec<String> = segments
+            .iter()
+            .map(|(id, data)| self.paint_segment(*id, data))
+            .collect();
+
+        rendered.join(&self.paint_separator(row))
+    }
+
+    // Powerline: 每个段落都有背景色，箭头的前景色取左侧背景、背景色取右侧背景
+    fn render_powerline(&self, segments: &[(SegmentId, SegmentData)]) -> String {
+        let glyph = match self.config.layout.powerline_style.as_str() {
+            "rounded" => "\u{e0b4}", //
+            "slanted" => "\u{e0bc}", //
+            "plain" => "",
+            _ => "\u{e0b0}", //
+        };
+
+        let styles: Vec<Style> = segments
+            .iter()
+            .map(|(id, data)| self.powerline_style(*id, data))
+            .collect();
+
+        let mut output = String::new();
+        for (i, (id, data)) in segments.iter().enumerate() {
+            let pad = " ".repeat(self.padding_for(*id).max(1));
+            let painted = self.paint(&styles[i], &format!("{}{}{}", pad, data.content(), pad));
+            output.push_str(&self.link(painted, data));
 
-        // 末尾表情：每 2 秒切换一枚（基于当前时间计算）
-        if self.config.segments.emoji {
-            let emoji_segment = crate::core::segments::EmojiSegment::new(true);
-            let content = emoji_segment.render(input);
-            if !content.is_empty() {
-                segments.push(content);
+            if glyph.is_empty() {
+                continue;
             }
+            let mut joint = Style::new();
+            joint.fg = styles[i].bg;
+            joint.bg = styles.get(i + 1).and_then(|next| next.bg);
+            output.push_str(&self.paint(&joint, glyph));
         }
+        output
+    }
+
+    fn powerline_style(&self, id: SegmentId, data: &SegmentData) -> Style {
+        let mut style = self.theme.powerline_style_for(id);
+        if self.theme.state_colors {
+            // 状态色在 powerline 下作为背景色使用
+            if let Some(color) = data.bg.or(data.fg) {
+                style.bg = Some(color);
+                style.fg = Some(color.contrast_fg());
+            }
+        }
+        style
+    }
+
+    fn paint_separator(&self, row: &Row) -> String {
+        let color = if row.separator_color.is_empty() {
+            self.theme.separator
+        } else {
+            Color::from_name(&row.separator_color)
+        };
+        let style = match color {
+            Some(color) => Style::new().fg(color),
+            None => Style::new(),
+        };
+        self.paint(&style, &row.separator)
+    }
+
+    fn paint(&self, style: &Style, text: &str) -> String {
+        match self.format {
+            OutputFormat::Tmux => output::tmux_markup(style, text),
+            _ => style.paint(text, self.color_mode),
+        }
+    }
+
+    fn padding_for(&self, id: SegmentId) -> usize {
+        let layout = &self.config.layout;
+        layout
+            .segment_padding
+            .get(id.name())
+            .copied()
+            .unwrap_or(layout.padding)
+    }
+
+    fn segment_style(&self, id: SegmentId, data: &SegmentData) -> Style {
+        let mut style = self.theme.style_for(id);
+        if self.theme.state_colors {
+            if let Some(fg) = data.fg {
+                style.fg = Some(fg);
+            }
+            if let Some(bg) = data.bg {
+                style.bg = Some(bg);
+            }
+        }
+        style
+    }
 
-        // Join segments with white separator
-        segments.join("\x1b[37m | \x1b[0m")
+    fn paint_segment(&self, id: SegmentId, data: &SegmentData) -> String {
+        let style = self.segment_style(id, data);
+
+        // 有背景色的段落前后至少留一个空格
+        let mut padding = self.padding_for(id);
+        if style.bg.is_some() {
+            padding = padding.max(1);
+        }
+        let pad = " ".repeat(padding);
+        let painted = self.paint(&style, &format!("{}{}{}", pad, data.content(), pad));
+        self.link(painted, data)
+    }
+
+    // 终端支持时把整个段落包进 OSC 8 超链接
+    fn link(&self, painted: String, data: &SegmentData) -> String {
+        match &data.link {
+            Some(url) if self.hyperlinks => hyperlink(&painted, url),
+            _ => painted,
+        }
     }
 }
@@ -0,0 +1,385 @@
+// 终端样式：颜色与 SGR 转义序列的生成，统一由状态栏生成器调用
+use crate::core::terminal::TerminalEnv;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    /// 256-color palette index
+    Fixed(u8),
+    /// 24-bit color
+    Rgb(u8, u8, u8),
+}
+
+/// How many colors the output terminal can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    /// No escape sequences at all
+    None,
+}
+
+impl ColorMode {
+    /// `color_mode` config value: "auto", "truecolor", "256", "16" or "none"
+    pub fn from_config(value: &str, env: &TerminalEnv) -> ColorMode {
+        match value.trim().to_lowercase().as_str() {
+            "truecolor" | "24bit" => ColorMode::TrueColor,
+            "256" | "ansi256" => ColorMode::Ansi256,
+            "16" | "ansi16" | "ansi" => ColorMode::Ansi16,
+            "none" | "off" | "never" => ColorMode::None,
+            _ => Self::detect(env),
+        }
+    }
+
+    /// Detect from `NO_COLOR`, `COLORTERM` and `TERM`
+    pub fn detect(env: &TerminalEnv) -> ColorMode {
+        Self::from_env(env.var("NO_COLOR"), env.var("COLORTERM"), env.var("TERM"))
+    }
+
+    fn from_env(no_color: &str, colorterm: &str, term: &str) -> ColorMode {
+        // https://no-color.org: 任何非空值都表示禁用颜色
+        if !no_color.is_empty() {
+            return ColorMode::None;
+        }
+        let colorterm = colorterm.to_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorMode::TrueColor;
+        }
+        let term = term.to_lowercase();
+        if term == "dumb" {
+            ColorMode::None
+        } else if term.contains("direct") {
+            // xterm-direct 等 terminfo 条目直接使用 24 位颜色
+            ColorMode::TrueColor
+        } else if term.contains("256color") {
+            ColorMode::Ansi256
+        } else {
+            ColorMode::Ansi16
+        }
+    }
+}
+
+// xterm 默认的 16 色调色板，用于降级时寻找最接近的颜色
+const ANSI16_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+const ANSI16: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+    Color::BrightBlack,
+    Color::BrightRed,
+    Color::BrightGreen,
+    Color::BrightYellow,
+    Color::BrightBlue,
+    Color::BrightMagenta,
+    Color::BrightCyan,
+    Color::BrightWhite,
+];
+
+impl Color {
+    /// Parse a color name such as `cyan`, `bright_yellow` or `bright-blue`,
+    /// a 256-color index such as `208`, or a hex color such as `#268bd2`
+    pub fn from_name(name: &str) -> Option<Color> {
+        let name = name.trim();
+        if let Some(hex) = name.strip_prefix('#') {
+            return Self::from_hex(hex);
+        }
+        if let Ok(index) = name.parse::<u8>() {
+            return Some(Color::Fixed(index));
+        }
+
+        let normalized: String = name
+            .to_lowercase()
+            .chars()
+            .filter(|c| *c != '_' && *c != '-' && *c != ' ')
+            .collect();
+        let color = match normalized.as_str() {
+            "black" => Color::Black,
+            "red" => Color::Red,
+            "green" => Color::Green,
+            "yellow" => Color::Yellow,
+            "blue" => Color::Blue,
+            "magenta" | "purple" => Color::Magenta,
+            "cyan" => Color::Cyan,
+            "white" => Color::White,
+            "brightblack" | "gray" | "grey" => Color::BrightBlack,
+            "brightred" => Color::BrightRed,
+            "brightgreen" => Color::BrightGreen,
+            "brightyellow" => Color::BrightYellow,
+            "brightblue" => Color::BrightBlue,
+            "brightmagenta" | "brightpurple" => Color::BrightMagenta,
+            "brightcyan" => Color::BrightCyan,
+            "brightwhite" => Color::BrightWhite,
+            _ => return None,
+        };
+        Some(color)
+    }
+
+    fn from_hex(hex: &str) -> Option<Color> {
+        let hex = match hex.len() {
+            // #rgb 简写
+            3 => hex.chars().flat_map(|c| [c, c]).collect::<String>(),
+            6 => hex.to_string(),
+            _ => return None,
+        };
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    /// Readable text color on top of this color used as a background
+    pub fn contrast_fg(&self) -> Color {
+        let (r, g, b) = self.to_rgb();
+        let luminance = 0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64;
+        if luminance < 140.0 {
+            Color::BrightWhite
+        } else {
+            Color::Black
+        }
+    }
+
+    /// `#rrggbb` form, for outputs that take CSS-style colors
+    pub fn hex(&self) -> String {
+        let (r, g, b) = self.to_rgb();
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+
+    fn ansi_index(&self) -> Option<u8> {
+        ANSI16.iter().position(|c| c == self).map(|i| i as u8)
+    }
+
+    fn to_rgb(self) -> (u8, u8, u8) {
+        match self {
+            Color::Rgb(r, g, b) => (r, g, b),
+            Color::Fixed(index) => fixed_to_rgb(index),
+            named => ANSI16_RGB[named.ansi_index().unwrap_or(0) as usize],
+        }
+    }
+
+    /// Closest representable color for `mode`; `None` when colors are disabled
+    fn downgrade(self, mode: ColorMode) -> Option<Color> {
+        match (mode, self) {
+            (ColorMode::None, _) => None,
+            (ColorMode::TrueColor, color) => Some(color),
+            (ColorMode::Ansi256, Color::Rgb(r, g, b)) => Some(Color::Fixed(rgb_to_256(r, g, b))),
+            (ColorMode::Ansi256, color) => Some(color),
+            (ColorMode::Ansi16, Color::Fixed(index)) if index < 16 => Some(ANSI16[index as usize]),
+            (ColorMode::Ansi16, color @ (Color::Fixed(_) | Color::Rgb(..))) => {
+                let (r, g, b) = color.to_rgb();
+                Some(nearest_ansi16(r, g, b))
+            }
+            (ColorMode::Ansi16, color) => Some(color),
+        }
+    }
+
+    fn sgr(&self, background: bool) -> String {
+        match *self {
+            Color::Fixed(index) => format!("{};5;{}", if background { 48 } else { 38 }, index),
+            Color::Rgb(r, g, b) => {
+                format!("{};2;{};{};{}", if background { 48 } else { 38 }, r, g, b)
+            }
+            named => {
+                let idx = named.ansi_index().unwrap_or(0);
+                let base = match (background, idx < 8) {
+                    (false, true) => 30,
+                    (false, false) => 90 - 8,
+                    (true, true) => 40,
+                    (true, false) => 100 - 8,
+                };
+                (base + idx).to_string()
+            }
+        }
+    }
+
+    pub fn fg_code(&self) -> String {
+        self.sgr(false)
+    }
+
+    pub fn bg_code(&self) -> String {
+        self.sgr(true)
+    }
+}
+
+fn fixed_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16_RGB[index as usize],
+        16..=231 => {
+            let i = index - 16;
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            (level(i / 36), level((i / 6) % 6), level(i % 6))
+        }
+        _ => {
+            let v = 8 + (index - 232) * 10;
+            (v, v, v)
+        }
+    }
+}
+
+fn rgb_to_256(r: u8, g: u8, b: u8) -> u8 {
+    // 灰阶单独映射到 232-255，精度更高
+    if r == g && g == b {
+        return match r {
+            0..=7 => 16,
+            249..=255 => 231,
+            v => 232 + ((v as u16 - 8) * 24 / 241) as u8,
+        };
+    }
+    let level = |v: u8| ((v as u16 * 5 + 127) / 255) as u8;
+    16 + 36 * level(r) + 6 * level(g) + level(b)
+}
+
+fn nearest_ansi16(r: u8, g: u8, b: u8) -> Color {
+    let distance = |(cr, cg, cb): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(r, cr) + d(g, cg) + d(b, cb)
+    };
+    ANSI16_RGB
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, rgb)| distance(**rgb))
+        .map(|(i, _)| ANSI16[i])
+        .unwrap_or(Color::White)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    pub fn bg(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    fn sgr_codes(&self, mode: ColorMode) -> Vec<String> {
+        let mut codes = Vec::new();
+        if mode == ColorMode::None {
+            return codes;
+        }
+        if self.bold {
+            codes.push("1".to_string());
+        }
+        if let Some(fg) = self.fg.and_then(|c| c.downgrade(mode)) {
+            codes.push(fg.fg_code());
+        }
+        if let Some(bg) = self.bg.and_then(|c| c.downgrade(mode)) {
+            codes.push(bg.bg_code());
+        }
+        codes
+    }
+
+    /// Wrap `text` in the SGR sequence for this style, resetting afterwards.
+    /// Colors are downgraded to what `mode` supports.
+    pub fn paint(&self, text: &str, mode: ColorMode) -> String {
+        let codes = self.sgr_codes(mode);
+        if codes.is_empty() || text.is_empty() {
+            return text.to_string();
+        }
+        format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_env_prefers_no_color() {
+        assert_eq!(
+            ColorMode::from_env("1", "truecolor", "xterm-256color"),
+            ColorMode::None
+        );
+    }
+
+    #[test]
+    fn from_env_reads_colorterm_and_term() {
+        assert_eq!(
+            ColorMode::from_env("", "truecolor", "xterm"),
+            ColorMode::TrueColor
+        );
+        assert_eq!(ColorMode::from_env("", "24BIT", ""), ColorMode::TrueColor);
+        assert_eq!(
+            ColorMode::from_env("", "", "xterm-direct"),
+            ColorMode::TrueColor
+        );
+        assert_eq!(
+            ColorMode::from_env("", "", "xterm-256color"),
+            ColorMode::Ansi256
+        );
+        assert_eq!(ColorMode::from_env("", "", "xterm"), ColorMode::Ansi16);
+        assert_eq!(ColorMode::from_env("", "", ""), ColorMode::Ansi16);
+        assert_eq!(ColorMode::from_env("", "", "dumb"), ColorMode::None);
+    }
+
+    #[test]
+    fn rgb_to_256_maps_cube_and_grays() {
+        assert_eq!(rgb_to_256(255, 0, 0), 196);
+        assert_eq!(rgb_to_256(0, 135, 255), 39);
+        assert_eq!(rgb_to_256(0, 0, 0), 16);
+        assert_eq!(rgb_to_256(255, 255, 255), 231);
+        assert_eq!(rgb_to_256(128, 128, 128), 243);
+    }
+
+    #[test]
+    fn nearest_ansi16_picks_closest_palette_entry() {
+        assert_eq!(nearest_ansi16(0, 0, 0), Color::Black);
+        assert_eq!(nearest_ansi16(200, 0, 0), Color::Red);
+        assert_eq!(nearest_ansi16(250, 10, 10), Color::BrightRed);
+        assert_eq!(nearest_ansi16(128, 128, 128), Color::BrightBlack);
+        assert_eq!(nearest_ansi16(250, 250, 250), Color::BrightWhite);
+    }
+}
@@ -0,0 +1,273 @@
+// 段落格式模板
+//
+// 语法：
+//   {name}        插入字段
+//   {name:.2}     数字保留两位小数
+//   {name:k}      数字紧凑显示 (12.3k / 4.5M)，可与精度组合：{name:.0k}
+//   [ ... ]       条件段：内部引用的字段全部非空（文本非空、数字非零）时才显示，可嵌套
+//   \[ \] \{ \} \\  转义
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Field {
+    Text(String),
+    Number(f64),
+}
+
+impl Field {
+    fn is_empty(&self) -> bool {
+        match self {
+            Field::Text(text) => text.is_empty(),
+            Field::Number(value) => *value == 0.0,
+        }
+    }
+
+    fn format(&self, spec: &str) -> String {
+        match self {
+            Field::Text(text) => text.clone(),
+            Field::Number(value) => format_number(*value, spec),
+        }
+    }
+}
+
+// JSON 输出：整数不带小数点
+impl Serialize for Field {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Field::Text(text) => serializer.serialize_str(text),
+            Field::Number(value) if value.fract() == 0.0 && value.abs() < 1e15 => {
+                serializer.serialize_i64(*value as i64)
+            }
+            Field::Number(value) => serializer.serialize_f64(*value),
+        }
+    }
+}
+
+impl From<String> for Field {
+    fn from(value: String) -> Self {
+        Field::Text(value)
+    }
+}
+
+impl From<&str> for Field {
+    fn from(value: &str) -> Self {
+        Field::Text(value.to_string())
+    }
+}
+
+impl From<f64> for Field {
+    fn from(value: f64) -> Self {
+        Field::Number(value)
+    }
+}
+
+impl From<u32> for Field {
+    fn from(value: u32) -> Self {
+        Field::Number(value as f64)
+    }
+}
+
+impl From<usize> for Field {
+    fn from(value: usize) -> Self {
+        Field::Number(value as f64)
+    }
+}
+
+pub type Fields = BTreeMap<&'static str, Field>;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Literal(String),
+    Field { name: String, spec: String },
+    Section(Vec<Node>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+impl Template {
+    /// Parse a template. Unbalanced brackets are treated as literal text.
+    pub fn parse(source: &str) -> Self {
+        let chars: Vec<char> = source.chars().collect();
+        let mut pos = 0;
+        let nodes = parse_nodes(&chars, &mut pos, false);
+        Self { nodes }
+    }
+
+    pub fn render(&self, fields: &Fields) -> String {
+        render_nodes(&self.nodes, fields, false).unwrap_or_default()
+    }
+}
+
+fn parse_nodes(chars: &[char], pos: &mut usize, in_section: bool) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    let mut literal = String::new();
+
+    while *pos < chars.len() {
+        let c = chars[*pos];
+        match c {
+            '\\' if *pos + 1 < chars.len() => {
+                literal.push(chars[*pos + 1]);
+                *pos += 2;
+            }
+            '{' => {
+                let close = chars[*pos..].iter().position(|c| *c == '}');
+                match close {
+                    Some(offset) => {
+                        let inner: String = chars[*pos + 1..*pos + offset].iter().collect();
+                        let (name, spec) = match inner.split_once(':') {
+                            Some((name, spec)) => (name.trim(), spec.trim()),
+                            None => (inner.trim(), ""),
+                        };
+                        if !literal.is_empty() {
+                            nodes.push(Node::Literal(std::mem::take(&mut literal)));
+                        }
+                        nodes.push(Node::Field {
+                            name: name.to_string(),
+                            spec: spec.to_string(),
+                        });
+                        *pos += offset + 1;
+                    }
+                    None => {
+                        literal.push(c);
+                        *pos += 1;
+                    }
+                }
+            }
+            '[' => {
+                if !literal.is_empty() {
+                    nodes.push(Node::Literal(std::mem::take(&mut literal)));
+                }
+                *pos += 1;
+                let start = *pos;
+                let inner = parse_nodes(chars, pos, true);
+                if *pos < chars.len() && chars[*pos] == ']' {
+                    *pos += 1;
+                    nodes.push(Node::Section(inner));
+                } else {
+                    // 没有闭合的 ]：按字面量处理
+                    *pos = start;
+                    literal.push('[');
+                }
+            }
+            ']' if in_section => break,
+            _ => {
+                literal.push(c);
+                *pos += 1;
+            }
+        }
+    }
+
+    if !literal.is_empty() {
+        nodes.push(Node::Literal(literal));
+    }
+    nodes
+}
+
+/// Inside a section, `None` means a referenced field was empty
+fn render_nodes(nodes: &[Node], fields: &Fields, in_section: bool) -> Option<String> {
+    let mut output = String::new();
+    for node in nodes {
+        match node {
+            Node::Literal(text) => output.push_str(text),
+            Node::Field { name, spec } => match fields.get(name.as_str()) {
+                Some(field) if !(in_section && field.is_empty()) => {
+                    output.push_str(&field.format(spec))
+                }
+                _ if in_section => return None,
+                _ => {}
+            },
+            Node::Section(inner) => {
+                if let Some(text) = render_nodes(inner, fields, true) {
+                    output.push_str(&text);
+                }
+            }
+        }
+    }
+    Some(output)
+}
+
+fn format_number(value: f64, spec: &str) -> String {
+    let (spec, compact) = match spec.strip_suffix('k') {
+        Some(rest) => (rest, true),
+        None => (spec, false),
+    };
+    let precision = spec.strip_prefix('.').and_then(|p| p.parse::<usize>().ok());
+
+    if compact {
+        let (scaled, suffix) = if value.abs() >= 1_000_000.0 {
+            (value / 1_000_000.0, "M")
+        } else if value.abs() >= 1_000.0 {
+            (value / 1_000.0, "k")
+        } else {
+            (value, "")
+        };
+        let precision = precision.unwrap_or(if suffix.is_empty() { 0 } else { 1 });
+        return format!("{:.*}{}", precision, scaled, suffix);
+    }
+
+    match precision {
+        Some(precision) => format!("{:.*}", precision, value),
+        None if value.fract() == 0.0 => format!("{}", value as i64),
+        None => format!("{:.2}", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields() -> Fields {
+        let mut fields = Fields::new();
+        fields.insert("name", Field::from("main"));
+        fields.insert("empty", Field::from(""));
+        fields.insert("cost", Field::from(2.5));
+        fields.insert("zero", Field::from(0.0));
+        fields.insert("tokens", Field::from(12_345usize));
+        fields
+    }
+
+    fn render(source: &str) -> String {
+        Template::parse(source).render(&fields())
+    }
+
+    #[test]
+    fn inserts_fields_with_specs() {
+        assert_eq!(render("{name} ${cost:.2}"), "main $2.50");
+        assert_eq!(
+            render("{tokens:k} {tokens:.2k} {tokens}"),
+            "12.3k 12.35k 12345"
+        );
+        assert_eq!(render("{cost}"), "2.50");
+        assert_eq!(render("[{missing}]{name}"), "main");
+    }
+
+    #[test]
+    fn escapes_are_literal() {
+        assert_eq!(render(r"\[{name}\] \{name\} \\"), r"[main] {name} \");
+    }
+
+    #[test]
+    fn sections_need_all_fields() {
+        assert_eq!(render("{name}[ ${cost:.2}]"), "main $2.50");
+        assert_eq!(render("{name}[ ${zero:.2}]"), "main");
+        assert_eq!(render("{name}[ ({empty})]"), "main");
+        assert_eq!(render("{name}[ ({missing})]"), "main");
+    }
+
+    #[test]
+    fn nested_sections_drop_independently() {
+        assert_eq!(render("[{name}[ {empty}] {cost}]"), "main 2.50");
+        assert_eq!(render("[{empty}[ {name}]]"), "");
+    }
+
+    #[test]
+    fn unbalanced_brackets_are_literal() {
+        assert_eq!(render("[{name}"), "[main");
+        assert_eq!(render("{name"), "{name");
+        assert_eq!(render("{name}]"), "main]");
+    }
+}
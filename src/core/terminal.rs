@@ -0,0 +1,44 @@
+// 终端环境：颜色模式、超链接检测与宽度所依赖的信息
+// daemon 模式下由客户端采集并随请求发送，守护进程自身的环境与终端无关
+use crate::core::width::terminal_width;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// 检测逻辑读取的全部环境变量
+const VARS: [&str; 9] = [
+    "NO_COLOR",
+    "COLORTERM",
+    "TERM",
+    "TERM_PROGRAM",
+    "TMUX",
+    "WT_SESSION",
+    "KITTY_WINDOW_ID",
+    "KONSOLE_VERSION",
+    "VTE_VERSION",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TerminalEnv {
+    vars: BTreeMap<String, String>,
+    /// Columns of the terminal, if known
+    pub width: Option<usize>,
+}
+
+impl TerminalEnv {
+    /// Snapshot of the environment and terminal size of this process
+    pub fn current() -> Self {
+        let vars = VARS
+            .iter()
+            .filter_map(|name| Some((name.to_string(), std::env::var(name).ok()?)))
+            .collect();
+        Self {
+            vars,
+            width: terminal_width(),
+        }
+    }
+
+    /// Value of an environment variable, empty when unset
+    pub fn var(&self, name: &str) -> &str {
+        self.vars.get(name).map(String::as_str).unwrap_or("")
+    }
+}
@@ -0,0 +1,281 @@
+use crate::config::{Config, ConfigLoader, SegmentColorConfig};
+use crate::core::segments::SegmentId;
+use crate::core::style::{Color, Style};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+
+/// Custom theme file, e.g. `themes/ocean.toml` next to config.toml:
+///
+/// ```toml
+/// base = "dark"
+/// separator = "bright_black"
+///
+/// [segments.model]
+/// fg = "bright_cyan"
+/// bold = true
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ThemeFile {
+    base: Option<String>,
+    separator: Option<String>,
+    state_colors: Option<bool>,
+    segments: BTreeMap<String, SegmentColorConfig>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub name: String,
+    pub separator: Option<Color>,
+    /// Whether segments may recolor themselves by state (e.g. network latency)
+    pub state_colors: bool,
+    styles: BTreeMap<&'static str, Style>,
+}
+
+impl Theme {
+    /// Resolve `config.theme` (built-in name or custom file) and apply the
+    /// `[colors]` overrides from config.toml on top.
+    pub fn from_config(config: &Config) -> Self {
+        let mut theme = Self::load(&config.theme);
+        for (name, colors) in &config.colors {
+            theme.apply_override(name, colors);
+        }
+        theme
+    }
+
+    pub fn load(name: &str) -> Self {
+        if let Some(theme) = Self::builtin(name) {
+            return theme;
+        }
+
+        let path = ConfigLoader::themes_dir().join(format!("{}.toml", name));
+        let file = match fs::read_to_string(&path) {
+            Ok(content) => toml::from_str::<ThemeFile>(&content)
+                .map_err(|e| eprintln!("statusline: {}: {}", path.display(), e))
+                .ok(),
+            Err(_) => {
+                eprintln!(
+                    "statusline: unknown theme \"{}\" (no {}), using dark",
+                    name,
+                    path.display()
+                );
+                None
+            }
+        };
+
+        match file {
+            Some(file) => {
+                let base = file.base.as_deref().unwrap_or("dark");
+                let mut theme = Self::builtin(base).unwrap_or_else(Self::dark);
+                theme.name = name.to_string();
+                if let Some(separator) = file.separator.as_deref() {
+                    theme.separator = Color::from_name(separator);
+                }
+                if let Some(state_colors) = file.state_colors {
+                    theme.state_colors = state_colors;
+                }
+                for (segment, colors) in &file.segments {
+                    theme.apply_override(segment, colors);
+                }
+                theme
+            }
+            // 未知主题或主题文件有误时回退到默认的 dark
+            None => Self::dark(),
+        }
+    }
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        let theme = match name.trim().to_lowercase().as_str() {
+            "" | "dark" => Self::dark(),
+            "light" => Self::light(),
+            "solarized" => Self::solarized(),
+            "high-contrast" | "high_contrast" | "highcontrast" => Self::high_contrast(),
+            "mono" | "monochrome" => Self::mono(),
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    pub fn style_for(&self, id: SegmentId) -> Style {
+        self.styles.get(id.name()).copied().unwrap_or_default()
+    }
+
+    /// Style for powerline mode, where every segment needs a background.
+    /// Segments without a themed background use their foreground color as
+    /// background with a contrasting text color.
+    pub fn powerline_style_for(&self, id: SegmentId) -> Style {
+        let style = self.style_for(id);
+        if style.bg.is_some() {
+            return style;
+        }
+        let bg = style.fg.unwrap_or(Color::BrightBlack);
+        Style {
+            fg: Some(bg.contrast_fg()),
+            bg: Some(bg),
+            bold: style.bold,
+        }
+    }
+
+    fn apply_override(&mut self, segment: &str, colors: &SegmentColorConfig) {
+        let Some(id) = SegmentId::from_name(segment) else {
+            return;
+        };
+        let style = self.styles.entry(id.name()).or_default();
+        if let Some(fg) = colors.fg.as_deref() {
+            style.fg = Color::from_name(fg);
+        }
+        if let Some(bg) = colors.bg.as_deref() {
+            style.bg = Color::from_name(bg);
+        }
+        if let Some(bold) = colors.bold {
+            style.bold = bold;
+        }
+    }
+
+    fn new(name: &str, separator: Option<Color>, styles: &[(SegmentId, Style)]) -> Self {
+        Self {
+            name: name.to_string(),
+            separator,
+            state_colors: true,
+            styles: styles
+                .iter()
+                .map(|(id, style)| (id.name(), *style))
+                .collect(),
+        }
+    }
+
+    fn dark() -> Self {
+        Self::new(
+            "dark",
+            Some(Color::White),
+            &[
+                (
+                    SegmentId::Spinner,
+                    Style::new().fg(Color::Green).bg(Color::White).bold(),
+                ),
+                (SegmentId::Model, Style::new().fg(Color::Cyan).bold()),
+                (SegmentId::Directory, Style::new().fg(Color::Green).bold()),
+                (SegmentId::Git, Style::new().fg(Color::Blue).bold()),
+                (SegmentId::Usage, Style::new().fg(Color::Magenta).bold()),
+                (
+                    SegmentId::Quota,
+                    Style::new().fg(Color::BrightYellow).bold(),
+                ),
+                (SegmentId::Monthly, Style::new().fg(Color::Yellow)),
+                (
+                    SegmentId::Network,
+                    Style::new().fg(Color::BrightBlue).bold(),
+                ),
+                (SegmentId::Ranking, Style::new().fg(Color::Cyan)),
+                (SegmentId::Token, Style::new().fg(Color::Yellow)),
+                (SegmentId::Time, Style::new().fg(Color::White)),
+                (SegmentId::Emoji, Style::new()),
+            ],
+        )
+    }
+
+    fn light() -> Self {
+        Self::new(
+            "light",
+            Some(Color::BrightBlack),
+            &[
+                (
+                    SegmentId::Spinner,
+                    Style::new().fg(Color::BrightWhite).bg(Color::Blue).bold(),
+                ),
+                (SegmentId::Model, Style::new().fg(Color::Blue).bold()),
+                (SegmentId::Directory, Style::new().fg(Color::Green)),
+                (SegmentId::Git, Style::new().fg(Color::Magenta)),
+                (SegmentId::Usage, Style::new().fg(Color::Red)),
+                (SegmentId::Quota, Style::new().fg(Color::Yellow).bold()),
+                (SegmentId::Monthly, Style::new().fg(Color::Yellow)),
+                (SegmentId::Network, Style::new().fg(Color::Blue)),
+                (SegmentId::Ranking, Style::new().fg(Color::Cyan)),
+                (SegmentId::Token, Style::new().fg(Color::Red)),
+                (SegmentId::Time, Style::new().fg(Color::Black)),
+                (SegmentId::Emoji, Style::new()),
+            ],
+        )
+    }
+
+    // Solarized 官方色值，终端不支持真彩色时自动降级
+    fn solarized() -> Self {
+        let base01 = Color::Rgb(0x58, 0x6e, 0x75);
+        let base3 = Color::Rgb(0xfd, 0xf6, 0xe3);
+        let yellow = Color::Rgb(0xb5, 0x89, 0x00);
+        let orange = Color::Rgb(0xcb, 0x4b, 0x16);
+        let magenta = Color::Rgb(0xd3, 0x36, 0x82);
+        let violet = Color::Rgb(0x6c, 0x71, 0xc4);
+        let blue = Color::Rgb(0x26, 0x8b, 0xd2);
+        let cyan = Color::Rgb(0x2a, 0xa1, 0x98);
+        let green = Color::Rgb(0x85, 0x99, 0x00);
+        Self::new(
+            "solarized",
+            Some(base01),
+            &[
+                (SegmentId::Spinner, Style::new().fg(base3).bg(cyan)),
+                (SegmentId::Model, Style::new().fg(blue)),
+                (SegmentId::Directory, Style::new().fg(cyan)),
+                (SegmentId::Git, Style::new().fg(green)),
+                (SegmentId::Usage, Style::new().fg(magenta)),
+                (SegmentId::Quota, Style::new().fg(yellow)),
+                (SegmentId::Monthly, Style::new().fg(orange)),
+                (SegmentId::Network, Style::new().fg(violet)),
+                (SegmentId::Ranking, Style::new().fg(orange)),
+                (SegmentId::Token, Style::new().fg(yellow)),
+                (SegmentId::Time, Style::new().fg(base01)),
+                (SegmentId::Emoji, Style::new()),
+            ],
+        )
+    }
+
+    fn high_contrast() -> Self {
+        Self::new(
+            "high-contrast",
+            Some(Color::BrightWhite),
+            &[
+                (
+                    SegmentId::Spinner,
+                    Style::new().fg(Color::Black).bg(Color::BrightYellow).bold(),
+                ),
+                (SegmentId::Model, Style::new().fg(Color::BrightWhite).bold()),
+                (
+                    SegmentId::Directory,
+                    Style::new().fg(Color::BrightYellow).bold(),
+                ),
+                (SegmentId::Git, Style::new().fg(Color::BrightCyan).bold()),
+                (
+                    SegmentId::Usage,
+                    Style::new().fg(Color::BrightMagenta).bold(),
+                ),
+                (SegmentId::Quota, Style::new().fg(Color::BrightGreen).bold()),
+                (SegmentId::Monthly, Style::new().fg(Color::BrightGreen)),
+                (
+                    SegmentId::Network,
+                    Style::new().fg(Color::BrightWhite).bold(),
+                ),
+                (
+                    SegmentId::Ranking,
+                    Style::new().fg(Color::BrightYellow).bold(),
+                ),
+                (SegmentId::Token, Style::new().fg(Color::BrightRed).bold()),
+                (SegmentId::Time, Style::new().fg(Color::BrightWhite).bold()),
+                (SegmentId::Emoji, Style::new()),
+            ],
+        )
+    }
+
+    fn mono() -> Self {
+        let mut theme = Self::new(
+            "mono",
+            None,
+            &[
+                (SegmentId::Model, Style::new().bold()),
+                (SegmentId::Quota, Style::new().bold()),
+            ],
+        );
+        theme.state_colors = false;
+        theme
+    }
+}
@@ -0,0 +1,147 @@
+// 显示宽度计算：颜文字、CJK 与 emoji 按终端单元格宽度计数
+use unicode_width::UnicodeWidthStr;
+
+const ELLIPSIS: &str = "…";
+
+/// Number of terminal cells `text` occupies; escape sequences are not counted
+pub fn display_width(text: &str) -> usize {
+    if !text.contains('\x1b') {
+        return text.width();
+    }
+    strip_ansi(text).width()
+}
+
+fn strip_ansi(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            output.push(c);
+            continue;
+        }
+        match chars.peek() {
+            // CSI: ESC [ ... 终止于 @-~ 之间的字节
+            Some('[') => {
+                chars.next();
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: ESC ] ... 终止于 BEL 或 ESC \
+            Some(']') => {
+                chars.next();
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    output
+}
+
+/// Cut `text` to at most `max_width` cells, ending with an ellipsis when shortened
+pub fn truncate_to_width(text: &str, max_width: usize) -> String {
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+
+    let budget = max_width - ELLIPSIS.width();
+    let mut output = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.to_string().width();
+        if used + w > budget {
+            break;
+        }
+        output.push(c);
+        used += w;
+    }
+    output.trim_end().to_string() + ELLIPSIS
+}
+
+/// Width available for the statusline: `COLUMNS`, then the size of the
+/// controlling terminal. `None` when neither is known.
+pub fn terminal_width() -> Option<usize> {
+    if let Some(columns) = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|columns| *columns > 0)
+    {
+        return Some(columns);
+    }
+    tty_width()
+}
+
+#[cfg(unix)]
+fn tty_width() -> Option<usize> {
+    use std::os::unix::io::AsRawFd;
+
+    // stdout 通常是管道，直接询问控制终端
+    let tty = std::fs::File::open("/dev/tty").ok()?;
+    unsafe {
+        let mut size: libc::winsize = std::mem::zeroed();
+        if libc::ioctl(tty.as_raw_fd(), libc::TIOCGWINSZ, &mut size) == 0 && size.ws_col > 0 {
+            Some(size.ws_col as usize)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn tty_width() -> Option<usize> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_ansi_removes_csi_and_osc() {
+        assert_eq!(strip_ansi("\x1b[1;34mOpus\x1b[0m"), "Opus");
+        assert_eq!(
+            strip_ansi("\x1b]8;;https://example.com\x1b\\main\x1b]8;;\x1b\\"),
+            "main"
+        );
+        assert_eq!(strip_ansi("\x1b]8;;file:///tmp\x07tmp\x1b]8;;\x07"), "tmp");
+    }
+
+    #[test]
+    fn display_width_counts_wide_characters() {
+        assert_eq!(display_width("\x1b[32m项目\x1b[0m"), 4);
+        assert_eq!(display_width("🤖 Opus"), 7);
+    }
+
+    #[test]
+    fn truncate_keeps_text_that_fits() {
+        assert_eq!(truncate_to_width("main", 4), "main");
+        assert_eq!(truncate_to_width("feature", 0), "");
+    }
+
+    #[test]
+    fn truncate_ends_with_ellipsis() {
+        assert_eq!(truncate_to_width("feature/branch", 8), "feature…");
+        assert_eq!(truncate_to_width("feature branch", 9), "feature…");
+    }
+
+    #[test]
+    fn truncate_never_splits_wide_characters() {
+        // 每个汉字占两列：5 列只能放下两个汉字加省略号
+        assert_eq!(truncate_to_width("中文目录名", 5), "中文…");
+        assert_eq!(truncate_to_width("中文目录名", 4), "中…");
+        assert!(display_width(&truncate_to_width("中文目录名", 6)) <= 6);
+    }
+}
@@ -0,0 +1,247 @@
+// 后台渲染进程：通过 Unix socket 接收 Claude Code 的输入并返回渲染结果，
+// 让 HTTP、git 与网络探测的缓存在多次渲染之间保持有效
+use crate::config::token::TOKEN_ENV;
+use crate::config::{Config, DaemonConfig, InputData};
+use crate::core::cache::fnv1a;
+use crate::core::output::OutputFormat;
+use crate::core::providers::anthropic::ADMIN_KEY_ENV;
+use crate::core::terminal::TerminalEnv;
+use crate::core::StatusLineGenerator;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Everything a render depends on besides config.toml. Sent by the client
+/// as one JSON line; the daemon answers with one `RenderResponse` line.
+#[derive(Serialize, Deserialize)]
+pub struct RenderRequest {
+    pub input: InputData,
+    /// The client's `--config` (see `config_key`); a daemon started with
+    /// another config closes the connection and the client renders itself
+    #[serde(default)]
+    pub config: Option<PathBuf>,
+    /// `environment_key` of the client; the daemon only serves clients whose
+    /// credentials and relay settings match its own
+    #[serde(default)]
+    pub environment: u64,
+    pub format: String,
+    /// `--theme` override
+    pub theme: Option<String>,
+    /// The client's terminal, used for color, hyperlink and width detection
+    pub terminal: TerminalEnv,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RenderResponse {
+    output: String,
+}
+
+impl RenderRequest {
+    pub fn render(&self, mut config: Config) -> String {
+        if let Some(theme) = &self.theme {
+            config.theme = theme.clone();
+        }
+        let format = OutputFormat::from_name(&self.format).unwrap_or(OutputFormat::Ansi);
+        StatusLineGenerator::new_with_terminal(config, self.terminal.clone())
+            .with_format(format)
+            .generate(&self.input)
+    }
+}
+
+/// `--config` path as compared between client and daemon: absolute when it
+/// exists, `None` for the default config.toml
+pub fn config_key(path: Option<&Path>) -> Option<PathBuf> {
+    path.map(|path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf()))
+}
+
+// 决定 API 地址、密钥与 token 的环境变量；daemon 读取的是它启动时的值
+const CREDENTIAL_VARS: [&str; 7] = [
+    "HOME",
+    "ANTHROPIC_BASE_URL",
+    "ANTHROPIC_AUTH_TOKEN",
+    "ANTHROPIC_API_KEY",
+    "INFO_URL",
+    ADMIN_KEY_ENV,
+    TOKEN_ENV,
+];
+
+/// Hash of the variables that select API keys, relay URLs and the JWT (and
+/// `HOME`, for settings.json). Only the hash is sent, never the values.
+pub fn environment_key() -> u64 {
+    let values: String = CREDENTIAL_VARS
+        .iter()
+        .map(|name| format!("{}={}\n", name, std::env::var(name).unwrap_or_default()))
+        .collect();
+    fnv1a(&values)
+}
+
+/// `[daemon] socket`, or `$XDG_RUNTIME_DIR/ccline.sock`, or a per-user
+/// socket in the temp directory
+pub fn socket_path(config: &DaemonConfig) -> PathBuf {
+    if !config.socket.is_empty() {
+        return PathBuf::from(&config.socket);
+    }
+    if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR").filter(|dir| !dir.is_empty()) {
+        return PathBuf::from(dir).join("ccline.sock");
+    }
+    std::env::temp_dir().join(format!("ccline-{}.sock", user_id()))
+}
+
+#[cfg(unix)]
+fn user_id() -> u32 {
+    unsafe { libc::getuid() }
+}
+
+#[cfg(not(unix))]
+fn user_id() -> u32 {
+    0
+}
+
+/// Render through a running daemon. `None` when no daemon answers within
+/// `timeout_ms`, in which case the caller renders in-process.
+#[cfg(unix)]
+pub fn render_remote(config: &DaemonConfig, request: &RenderRequest) -> Option<String> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::fs::MetadataExt;
+    use std::os::unix::net::UnixStream;
+    use std::time::Duration;
+
+    // 只连接当前用户创建的 socket，避免把输入交给其他用户的进程
+    let path = socket_path(config);
+    if std::fs::metadata(&path).ok()?.uid() != user_id() {
+        return None;
+    }
+    let mut stream = UnixStream::connect(&path).ok()?;
+    let timeout = Duration::from_millis(config.timeout_ms.max(1));
+    stream.set_read_timeout(Some(timeout)).ok()?;
+    stream.set_write_timeout(Some(timeout)).ok()?;
+
+    let mut line = serde_json::to_string(request).ok()?;
+    line.push('\n');
+    stream.write_all(line.as_bytes()).ok()?;
+
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response).ok()?;
+    let response: RenderResponse = serde_json::from_str(&response).ok()?;
+    Some(response.output)
+}
+
+#[cfg(not(unix))]
+pub fn render_remote(_config: &DaemonConfig, _request: &RenderRequest) -> Option<String> {
+    None
+}
+
+/// Serve renders on the daemon socket until the process is killed.
+/// Config (`config_path`, or the default config.toml) is re-read for every
+/// request, so edits apply without a restart.
+#[cfg(unix)]
+pub fn run(config: &DaemonConfig, config_path: Option<PathBuf>) -> io::Result<()> {
+    use crate::config::ConfigLoader;
+    use crate::core::cache;
+    use std::fs;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    let path = socket_path(config);
+    if UnixStream::connect(&path).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            format!("daemon already running on {}", path.display()),
+        ));
+    }
+    // 上次退出时遗留的 socket 文件
+    let _ = fs::remove_file(&path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // 在 bind 之前设置 umask，socket 一创建就是 600，不留其他用户连接的窗口
+    let umask = unsafe { libc::umask(0o177) };
+    let bound = UnixListener::bind(&path);
+    unsafe { libc::umask(umask) };
+    let listener = bound?;
+    eprintln!("statusline daemon listening on {}", path.display());
+
+    let config_path = config_key(config_path.as_deref());
+    let environment = environment_key();
+
+    // 最近一次请求，后台线程定期用它刷新缓存
+    let last_request: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+
+    if config.refresh_interval > 0 {
+        let interval = Duration::from_secs(config.refresh_interval);
+        let last_request = Arc::clone(&last_request);
+        let config_path = config_path.clone();
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+            let line = last_request.lock().ok().and_then(|last| last.clone());
+            if let Some(request) = line.and_then(|line| parse_request(&line)) {
+                let config = ConfigLoader::load_from(config_path.as_deref());
+                cache::refreshing(|| request.render(config));
+            }
+        });
+    }
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let last_request = Arc::clone(&last_request);
+                let config_path = config_path.clone();
+                thread::spawn(move || {
+                    if let Err(err) =
+                        serve(stream, config_path.as_deref(), environment, &last_request)
+                    {
+                        eprintln!("statusline daemon: {}", err);
+                    }
+                });
+            }
+            Err(err) => eprintln!("statusline daemon: {}", err),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn run(_config: &DaemonConfig, _config_path: Option<PathBuf>) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "the daemon needs Unix domain sockets",
+    ))
+}
+
+#[cfg(unix)]
+fn serve(
+    stream: std::os::unix::net::UnixStream,
+    config_path: Option<&std::path::Path>,
+    environment: u64,
+    last_request: &std::sync::Mutex<Option<String>>,
+) -> io::Result<()> {
+    use crate::config::ConfigLoader;
+    use std::io::{BufRead, BufReader, Write};
+
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line)?;
+    let request = parse_request(&line)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid render request"))?;
+    // 客户端使用了另一份配置或另一组密钥：不回复，由客户端在本进程内渲染
+    if request.config.as_deref() != config_path || request.environment != environment {
+        return Ok(());
+    }
+
+    let response = RenderResponse {
+        output: request.render(ConfigLoader::load_from(config_path)),
+    };
+    let mut reply = serde_json::to_string(&response)?;
+    reply.push('\n');
+    (&stream).write_all(reply.as_bytes())?;
+
+    if let Ok(mut last) = last_request.lock() {
+        *last = Some(line);
+    }
+    Ok(())
+}
+
+fn parse_request(line: &str) -> Option<RenderRequest> {
+    serde_json::from_str(line).ok()
+}
@@ -1,14 +1,20 @@
-use ccometixline::cli::Cli;
-use ccometixline::config::{Config, ConfigLoader, InputData};
-use ccometixline::core::StatusLineGenerator;
-use std::io;
+use ccometixline::cli::{Cli, Command, TokenCommand};
+use ccometixline::config::token::format_remaining;
+use ccometixline::config::{Config, ConfigLoader, InputData, JwtClaims, JwtTokenSources};
+use ccometixline::core::cache;
+use ccometixline::core::output::OutputFormat;
+use ccometixline::core::terminal::TerminalEnv;
+use ccometixline::daemon::{self, RenderRequest};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[cfg(windows)]
 fn is_stdin_piped() -> bool {
     use winapi::um::fileapi::GetFileType;
-    use winapi::um::winbase::{FILE_TYPE_PIPE, FILE_TYPE_DISK, STD_INPUT_HANDLE};
     use winapi::um::processenv::GetStdHandle;
-    
+    use winapi::um::winbase::{FILE_TYPE_DISK, FILE_TYPE_PIPE, STD_INPUT_HANDLE};
+
     unsafe {
         let handle = GetStdHandle(STD_INPUT_HANDLE);
         let file_type = GetFileType(handle);
@@ -47,25 +53,129 @@ fn main() -> io::Result<()> {
         println!("TUI configuration mode not implemented yet");
         return Ok(());
     }
-    
+
     // Load configuration
-    let config = ConfigLoader::load();
+    let config_path = cli.config.as_ref().map(PathBuf::from);
+    let config = ConfigLoader::load_from(config_path.as_deref());
+
+    match cli.command {
+        Some(Command::Daemon) => return daemon::run(&config.daemon, config_path),
+        Some(Command::Token {
+            command: TokenCommand::Check,
+        }) => {
+            if !check_token(&config) {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        None => {}
+    }
+
+    let format = OutputFormat::from_name(&cli.format).expect("--format is validated by clap");
 
-    // Check if stdin is piped
-    if !is_stdin_piped() {
+    // Read Claude Code data from stdin; tmux and desktop bars run us
+    // without input and get the segments of the current directory
+    let input: InputData = if is_stdin_piped() {
+        let stdin = io::stdin();
+        serde_json::from_reader(stdin.lock())?
+    } else if format != OutputFormat::Ansi {
+        InputData::from_current_dir()
+    } else {
         // No piped input
         println!("请通过管道提供输入数据");
         return Ok(());
-    }
+    };
+
+    let request = RenderRequest {
+        input,
+        config: daemon::config_key(config_path.as_deref()),
+        environment: daemon::environment_key(),
+        format: cli.format,
+        theme: cli.theme,
+        terminal: TerminalEnv::current(),
+    };
 
-    // Read Claude Code data from stdin
-    let stdin = io::stdin();
-    let input: InputData = serde_json::from_reader(stdin.lock())?;
+    // 优先交给后台 daemon 渲染，未运行或超时则在本进程内生成
+    if !cli.no_daemon {
+        if let Some(statusline) = daemon::render_remote(&config.daemon, &request) {
+            println!("{}", statusline);
+            return Ok(());
+        }
+    }
 
     // Generate statusline
-    let generator = StatusLineGenerator::new(config);
-    let statusline = generator.generate(&input);
-    println!("{}", statusline);
+    println!("{}", request.render(config));
+    finish_revalidation();
 
     Ok(())
 }
+
+// 输出后把 stdout 换成 /dev/null 让 Claude Code 立即拿到结果（管道写端随之关闭），
+// 再等待超时的段落与后台刷新写回磁盘缓存。用 dup2 而不是 close，fd 1 不会被之后打开的文件复用
+fn finish_revalidation() {
+    let _ = io::stdout().flush();
+    #[cfg(unix)]
+    if let Ok(null) = std::fs::OpenOptions::new().write(true).open("/dev/null") {
+        use std::os::unix::io::AsRawFd;
+        unsafe {
+            libc::dup2(null.as_raw_fd(), libc::STDOUT_FILENO);
+        }
+    }
+    // 足够 ping -W 3 与连续几次 HTTP 请求完成
+    cache::wait_for_revalidation(Duration::from_secs(5));
+}
+
+// statusline token check：打印 token 的声明与过期时间，token 不可用时返回 false
+fn check_token(config: &Config) -> bool {
+    let token = match JwtTokenSources::from_config(config).resolve() {
+        Ok(token) => token,
+        Err(err) => {
+            eprintln!("{}", err);
+            return false;
+        }
+    };
+    let Some(claims) = JwtClaims::decode(&token) else {
+        eprintln!("token 不是有效的 JWT");
+        return false;
+    };
+
+    let local_time = |secs: i64| {
+        chrono::DateTime::from_timestamp(secs, 0)
+            .map(|time| {
+                time.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S %:z")
+                    .to_string()
+            })
+            .unwrap_or_else(|| secs.to_string())
+    };
+    let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+
+    println!("username:    {}", or_dash(claims.username.clone()));
+    println!("email:       {}", or_dash(claims.email.clone()));
+    println!("permissions: {}", claims.permissions.join(", "));
+    println!("issued:      {}", or_dash(claims.iat.map(local_time)));
+
+    let now = chrono::Utc::now().timestamp();
+    match (claims.exp, claims.expires_in(now)) {
+        (Some(exp), Some(remaining)) if remaining > 0 => {
+            println!(
+                "expires:     {} (in {})",
+                local_time(exp),
+                format_remaining(remaining)
+            );
+            true
+        }
+        (Some(exp), Some(remaining)) => {
+            println!(
+                "expires:     {} (expired {} ago)",
+                local_time(exp),
+                format_remaining(-remaining)
+            );
+            false
+        }
+        _ => {
+            println!("expires:     -");
+            true
+        }
+    }
+}
@@ -0,0 +1,230 @@
+// 集成测试共用：本地 PackyCode 桩服务器与 statusline 进程
+#![allow(dead_code)]
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+pub const USER_INFO: &str = r#"{"daily_budget_usd":"20","daily_spent_usd":"2.5","monthly_budget_usd":"500","monthly_spent_usd":"40","opus_enabled":true}"#;
+pub const PEER_SPENDING: &str = r#"{"account_id":"a","date":"2026-01-01","timezone":"UTC","peers":[{"display_name":"x","spent_usd_today":"9.5","user_id":"1"},{"display_name":"y","spent_usd_today":"1.0","user_id":"2"}]}"#;
+/// Route body answered with 404 Not Found
+pub const NOT_FOUND: &str = "";
+/// Route body answered with 401 Unauthorized, as for an expired token
+pub const UNAUTHORIZED: &str = r#"{"error":"unauthorized"}"#;
+pub const INPUT: &str =
+    r#"{"model":{"display_name":"Opus"},"workspace":{"current_dir":"/"},"transcript_path":""}"#;
+
+/// HTTP server answering every request after `delay`: peer-spending paths
+/// get `PEER_SPENDING`, everything else `USER_INFO`. Records request paths.
+pub struct StubServer {
+    pub url: String,
+    paths: Arc<Mutex<Vec<String>>>,
+}
+
+impl StubServer {
+    pub fn start(delay: Duration) -> Self {
+        Self::with_routes(delay, &[("peer-spending", PEER_SPENDING)])
+    }
+
+    /// Answer paths containing a route's pattern with its body; the first
+    /// match wins and unmatched paths get `USER_INFO`; `NOT_FOUND` and
+    /// `UNAUTHORIZED` bodies answer 404 and 401
+    pub fn with_routes(delay: Duration, routes: &[(&str, &str)]) -> Self {
+        let routes: Arc<Vec<(String, String)>> = Arc::new(
+            routes
+                .iter()
+                .map(|(pattern, body)| (pattern.to_string(), body.to_string()))
+                .collect(),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let paths = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&paths);
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let recorded = Arc::clone(&recorded);
+                let routes = Arc::clone(&routes);
+                thread::spawn(move || {
+                    let mut reader = BufReader::new(&stream);
+                    let mut line = String::new();
+                    let _ = reader.read_line(&mut line);
+                    let path = line.split_whitespace().nth(1).unwrap_or("").to_string();
+                    line.clear();
+                    while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                        line.clear();
+                    }
+                    recorded.lock().unwrap().push(path.clone());
+
+                    thread::sleep(delay);
+                    let body = routes
+                        .iter()
+                        .find(|(pattern, _)| path.contains(pattern.as_str()))
+                        .map_or(USER_INFO, |(_, body)| body.as_str());
+                    let status = match body {
+                        NOT_FOUND => "404 Not Found",
+                        UNAUTHORIZED => "401 Unauthorized",
+                        _ => "200 OK",
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                    let _ = (&stream).write_all(response.as_bytes());
+                });
+            }
+        });
+
+        Self { url, paths }
+    }
+
+    pub fn requests(&self) -> usize {
+        self.paths.lock().unwrap().len()
+    }
+
+    pub fn paths(&self) -> Vec<String> {
+        self.paths.lock().unwrap().clone()
+    }
+}
+
+/// Unsigned JWT with the given payload
+pub fn jwt(claims: &str) -> String {
+    format!(
+        "{}.{}.sig",
+        URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#),
+        URL_SAFE_NO_PAD.encode(claims)
+    )
+}
+
+/// Temporary home directory, removed when dropped, also when a test fails
+pub struct TestHome(PathBuf);
+
+impl Deref for TestHome {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TestHome {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Home directory with Claude Code settings pointing at the stub, and a
+/// config.toml showing only `segment` and waiting for it without a deadline
+pub fn setup(name: &str, server: &StubServer, segment: &str, extra: &str) -> TestHome {
+    setup_with_deadline(name, server, segment, Some(0), extra)
+}
+
+/// Like `setup`, with `deadline_ms` set, or left at its default for `None`
+pub fn setup_with_deadline(
+    name: &str,
+    server: &StubServer,
+    segment: &str,
+    deadline_ms: Option<u64>,
+    extra: &str,
+) -> TestHome {
+    let home = std::env::temp_dir().join(format!("ccline-test-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&home);
+    fs::create_dir_all(home.join(".claude")).unwrap();
+    fs::write(
+        home.join(".claude/settings.json"),
+        format!(
+            r#"{{"env":{{"ANTHROPIC_BASE_URL":"{}","ANTHROPIC_AUTH_TOKEN":"test-key"}}}}"#,
+            server.url
+        ),
+    )
+    .unwrap();
+
+    let segments = [
+        "directory",
+        "git",
+        "model",
+        "usage",
+        "quota",
+        "time",
+        "emoji",
+        "spinner",
+        "network",
+        "ranking",
+        "token",
+        "monthly",
+    ]
+    .iter()
+    .map(|name| format!("{} = {}\n", name, *name == segment))
+    .collect::<String>();
+    let timing = deadline_ms
+        .map(|ms| format!("[timing]\ndeadline_ms = {}\n\n", ms))
+        .unwrap_or_default();
+    fs::write(
+        home.join("config.toml"),
+        format!(
+            "theme = \"dark\"\nfirst_run = false\n\n[segments]\n{}\n[layout]\norder = [\"{}\"]\n\n{}[cache]\ndir = \"{}\"\n\n{}\n",
+            segments,
+            segment,
+            timing,
+            home.join("cache").display(),
+            extra
+        ),
+    )
+    .unwrap();
+    TestHome(home)
+}
+
+/// Start a render with `INPUT` on stdin
+pub fn spawn_render(home: &Path, envs: &[(&str, &str)]) -> Child {
+    spawn_statusline(home, &["--no-daemon"], envs)
+}
+
+/// Start a render with `INPUT` on stdin that goes through a running daemon
+pub fn spawn_client(home: &Path, envs: &[(&str, &str)]) -> Child {
+    spawn_statusline(home, &[], envs)
+}
+
+fn spawn_statusline(home: &Path, args: &[&str], envs: &[(&str, &str)]) -> Child {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_statusline"));
+    command
+        .args(args)
+        .args(["--format", "plain", "--config"])
+        .arg(home.join("config.toml"))
+        .env("HOME", home)
+        .env("NO_PROXY", "127.0.0.1")
+        .env_remove("ANTHROPIC_ADMIN_KEY")
+        .env_remove("ANTHROPIC_API_KEY")
+        .env_remove("ANTHROPIC_AUTH_TOKEN")
+        .env_remove("ANTHROPIC_BASE_URL")
+        .env_remove("PACKYCODE_JWT_TOKEN")
+        .envs(envs.iter().copied())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped());
+    let mut child = command.spawn().unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(INPUT.as_bytes())
+        .unwrap();
+    child
+}
+
+pub fn output(child: Child) -> String {
+    String::from_utf8(child.wait_with_output().unwrap().stdout).unwrap()
+}
+
+/// Render once and return the output
+pub fn render(home: &Path, envs: &[(&str, &str)]) -> String {
+    output(spawn_render(home, envs))
+}
@@ -0,0 +1,67 @@
+// 多个 statusline 进程同时渲染时，同一接口只应被请求一次
+mod common;
+
+use common::{output, render, setup, setup_with_deadline, spawn_render, StubServer};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+// 桩服务器的地址不含 packycode.com，需要显式指定服务商
+const PACKYCODE: &str = "[quota]\nprovider = \"packycode\"\n";
+
+/// Start `count` renders at once and collect their outputs
+fn render_concurrently(home: &Path, count: usize) -> Vec<String> {
+    let children: Vec<_> = (0..count).map(|_| spawn_render(home, &[])).collect();
+    children.into_iter().map(output).collect()
+}
+
+#[test]
+fn concurrent_cold_renders_fetch_once() {
+    let server = StubServer::start(Duration::from_millis(300));
+    let home = setup("cold", &server, "quota", PACKYCODE);
+
+    let outputs = render_concurrently(&home, 8);
+
+    assert_eq!(server.requests(), 1);
+    for output in &outputs {
+        assert!(output.contains("2.50"), "unexpected output: {:?}", output);
+    }
+}
+
+#[test]
+fn concurrent_stale_renders_revalidate_once() {
+    let server = StubServer::start(Duration::from_millis(300));
+    let home = setup(
+        "stale",
+        &server,
+        "quota",
+        &format!("{}\n[cache.ttl]\nquota = 1", PACKYCODE),
+    );
+
+    render_concurrently(&home, 1);
+    assert_eq!(server.requests(), 1);
+    thread::sleep(Duration::from_millis(1100));
+
+    // 缓存已过期：全部立即显示旧值，只有一个进程在后台刷新
+    let outputs = render_concurrently(&home, 8);
+
+    assert_eq!(server.requests(), 2);
+    for output in &outputs {
+        assert!(output.contains("2.50"), "unexpected output: {:?}", output);
+    }
+}
+
+#[test]
+fn late_cold_fetch_is_cached_for_the_next_render() {
+    // 默认 300ms 时限内拿不到响应：先显示过期标记，进程退出前写回缓存
+    let server = StubServer::start(Duration::from_millis(500));
+    let home = setup_with_deadline("late", &server, "quota", None, PACKYCODE);
+
+    let first = render(&home, &[]);
+    assert!(first.contains('⏳'), "unexpected output: {:?}", first);
+    assert!(!first.contains("2.50"), "unexpected output: {:?}", first);
+
+    let second = render(&home, &[]);
+    assert!(second.contains("2.50"), "unexpected output: {:?}", second);
+    assert_eq!(server.requests(), 1);
+}
@@ -0,0 +1,181 @@
+// statusline daemon 的 socket 协议，以及客户端的 socket 所有者检查与超时回退
+#![cfg(unix)]
+mod common;
+
+use ccometixline::core::terminal::TerminalEnv;
+use ccometixline::daemon::{self, RenderRequest};
+use common::{output, setup, spawn_client, StubServer, TestHome, INPUT};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Home whose config.toml shows only the model segment and points the
+/// daemon at `<home>/daemon.sock`
+fn daemon_home(name: &str) -> TestHome {
+    let server = StubServer::start(Duration::ZERO);
+    // setup 使用的目录
+    let home = std::env::temp_dir().join(format!("ccline-test-{}-{}", name, std::process::id()));
+    setup(
+        name,
+        &server,
+        "model",
+        &format!(
+            "[daemon]\nsocket = \"{}\"\ntimeout_ms = 300\nrefresh_interval = 0",
+            socket_path(&home).display()
+        ),
+    )
+}
+
+fn socket_path(home: &Path) -> PathBuf {
+    home.join("daemon.sock")
+}
+
+/// `statusline daemon`, killed when dropped
+struct Daemon(Child);
+
+impl Daemon {
+    fn start(home: &Path) -> Self {
+        let child = Command::new(env!("CARGO_BIN_EXE_statusline"))
+            .arg("--config")
+            .arg(home.join("config.toml"))
+            .arg("daemon")
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while UnixStream::connect(socket_path(home)).is_err() {
+            assert!(Instant::now() < deadline, "daemon did not start");
+            thread::sleep(Duration::from_millis(20));
+        }
+        Daemon(child)
+    }
+}
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn request(home: &Path, environment: u64) -> RenderRequest {
+    RenderRequest {
+        input: serde_json::from_str(INPUT).unwrap(),
+        config: daemon::config_key(Some(&home.join("config.toml"))),
+        environment,
+        format: "plain".to_string(),
+        theme: None,
+        terminal: TerminalEnv::default(),
+    }
+}
+
+/// Send one request line and return the reply line, empty when the daemon
+/// closes the connection without answering
+fn send(home: &Path, request: &RenderRequest) -> String {
+    let mut stream = UnixStream::connect(socket_path(home)).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let mut line = serde_json::to_string(request).unwrap();
+    line.push('\n');
+    stream.write_all(line.as_bytes()).unwrap();
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply).unwrap();
+    reply
+}
+
+/// Socket that answers every request with `reply` after `delay`
+fn fake_daemon(home: &Path, delay: Duration, reply: &'static str) -> UnixListener {
+    let listener = UnixListener::bind(socket_path(home)).unwrap();
+    let accepting = listener.try_clone().unwrap();
+    thread::spawn(move || {
+        for stream in accepting.incoming().flatten() {
+            thread::spawn(move || {
+                let mut line = String::new();
+                let _ = BufReader::new(&stream).read_line(&mut line);
+                thread::sleep(delay);
+                let _ = (&stream).write_all(reply.as_bytes());
+            });
+        }
+    });
+    listener
+}
+
+#[test]
+fn daemon_renders_matching_request() {
+    let home = daemon_home("daemon-render");
+    let _daemon = Daemon::start(&home);
+
+    let reply = send(&home, &request(&home, daemon::environment_key()));
+
+    let reply: serde_json::Value = serde_json::from_str(&reply).unwrap();
+    let output = reply["output"].as_str().unwrap();
+    assert!(output.contains("Opus"), "unexpected output: {:?}", output);
+}
+
+#[test]
+fn daemon_socket_is_private() {
+    let home = daemon_home("daemon-mode");
+    let _daemon = Daemon::start(&home);
+
+    let mode = std::fs::metadata(socket_path(&home))
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+#[test]
+fn daemon_ignores_other_credentials_and_config() {
+    let home = daemon_home("daemon-mismatch");
+    let _daemon = Daemon::start(&home);
+
+    let other_env = request(&home, daemon::environment_key() ^ 1);
+    assert_eq!(send(&home, &other_env), "");
+
+    let mut other_config = request(&home, daemon::environment_key());
+    other_config.config = None;
+    assert_eq!(send(&home, &other_config), "");
+}
+
+#[test]
+fn client_prints_daemon_reply() {
+    let home = daemon_home("client-reply");
+    let _listener = fake_daemon(&home, Duration::ZERO, "{\"output\":\"from-daemon\"}\n");
+
+    let output = output(spawn_client(&home, &[]));
+
+    assert_eq!(output, "from-daemon\n");
+}
+
+#[test]
+fn client_falls_back_when_daemon_is_silent() {
+    let home = daemon_home("client-timeout");
+    let _listener = fake_daemon(&home, Duration::from_secs(5), "");
+
+    let started = Instant::now();
+    let output = output(spawn_client(&home, &[]));
+
+    // timeout_ms = 300 后在本进程内渲染
+    assert!(output.contains("Opus"), "unexpected output: {:?}", output);
+    assert!(started.elapsed() < Duration::from_secs(3));
+}
+
+#[test]
+fn client_ignores_socket_of_other_user() {
+    let home = daemon_home("client-owner");
+    let _listener = fake_daemon(&home, Duration::ZERO, "{\"output\":\"from-daemon\"}\n");
+    // 只有 root 才能把 socket 交给其他用户
+    if std::os::unix::fs::chown(socket_path(&home), Some(65534), None).is_err() {
+        return;
+    }
+
+    let output = output(spawn_client(&home, &[]));
+
+    assert!(output.contains("Opus"), "unexpected output: {:?}", output);
+    assert!(!output.contains("from-daemon"));
+}
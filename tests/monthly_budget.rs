@@ -0,0 +1,44 @@
+// monthly 段落：本月花费、预算占比与按匀速推算的月底总额
+mod common;
+
+use common::{render, setup, StubServer};
+use std::time::Duration;
+
+const NO_MONTHLY: &str = r#"{"remaining_credit_in_usd":7.5,"credit_limit_in_usd":10}"#;
+
+#[test]
+fn monthly_shows_budget_share() {
+    let server = StubServer::start(Duration::ZERO);
+    let home = setup(
+        "monthly",
+        &server,
+        "monthly",
+        "[quota]\nprovider = \"packycode\"",
+    );
+
+    let output = render(&home, &[]);
+
+    // users/info 返回本月 $40 / $500
+    assert_eq!(server.paths(), vec!["/api/backend/users/info".to_string()]);
+    assert!(
+        output.contains("Month: $40.00/$500 (8%)"),
+        "unexpected output: {:?}",
+        output
+    );
+}
+
+#[test]
+fn monthly_hidden_without_monthly_data() {
+    let server = StubServer::with_routes(Duration::ZERO, &[("/v1/dashboard/usage", NO_MONTHLY)]);
+    let home = setup(
+        "monthly-generic",
+        &server,
+        "monthly",
+        "[quota]\nprovider = \"generic\"",
+    );
+
+    let output = render(&home, &[]);
+
+    assert_eq!(server.paths(), vec!["/v1/dashboard/usage".to_string()]);
+    assert!(!output.contains("Month"), "unexpected output: {:?}", output);
+}
@@ -0,0 +1,76 @@
+// [packycode] 配置的接口地址：指向本地桩服务器并使用自定义路径
+mod common;
+
+use common::{jwt, render, setup, StubServer};
+use std::time::Duration;
+
+#[test]
+fn quota_uses_configured_user_info_path() {
+    let server = StubServer::start(Duration::ZERO);
+    let home = setup(
+        "quota-path",
+        &server,
+        "quota",
+        &format!(
+            "[packycode]\nbase_url = \"{}\"\nuser_info_path = \"/custom/info\"",
+            server.url
+        ),
+    );
+
+    let output = render(&home, &[]);
+
+    assert_eq!(server.paths(), vec!["/custom/info".to_string()]);
+    assert!(output.contains("2.50"), "unexpected output: {:?}", output);
+}
+
+#[test]
+fn ranking_uses_configured_base_url() {
+    let server = StubServer::start(Duration::ZERO);
+    let home = setup(
+        "ranking-base",
+        &server,
+        "ranking",
+        &format!(
+            "[packycode]\nbase_url = \"{}/\"\nuser_info_path = \"custom/info\"\npeer_spending_path = \"/custom/peer-spending\"",
+            server.url
+        ),
+    );
+
+    // 没有 exp 的 token
+    let token = jwt(r#"{"username":"tester"}"#);
+    let output = render(&home, &[("PACKYCODE_JWT_TOKEN", token.as_str())]);
+
+    let mut paths = server.paths();
+    paths.sort();
+    assert_eq!(paths, vec!["/custom/info", "/custom/peer-spending"]);
+    // 同行消费 9.5 与 1.0，自己 2.5：第 2 名
+    assert_eq!(output.trim_end(), "🥈 2");
+}
+
+#[test]
+fn quota_explains_unusable_token() {
+    let server = StubServer::start(Duration::ZERO);
+    let home = setup(
+        "quota-token",
+        &server,
+        "quota",
+        "[quota]\nprovider = \"packycode\"",
+    );
+
+    let missing = render(&home, &[]);
+    assert!(
+        missing.contains("Today: $2.50 | 排名已停用: 未配置 JWT token"),
+        "unexpected output: {:?}",
+        missing
+    );
+
+    let expired = jwt(r#"{"exp":1000}"#);
+    let output = render(&home, &[("PACKYCODE_JWT_TOKEN", expired.as_str())]);
+    assert!(
+        output.contains("Today: $2.50 | 排名已停用: token expired"),
+        "unexpected output: {:?}",
+        output
+    );
+    // 过期的 token 不会发给排名接口
+    assert!(server.paths().iter().all(|path| !path.contains("peer")));
+}
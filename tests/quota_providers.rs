@@ -0,0 +1,236 @@
+// [quota] provider：按 ANTHROPIC_BASE_URL 自动选择或显式指定服务商
+mod common;
+
+use common::{render, setup, StubServer, NOT_FOUND};
+use std::fs;
+use std::time::Duration;
+
+const BALANCE: &str = r#"{"data":{"used":"3.25","total":10,"currency":"CNY"}}"#;
+const SUBSCRIPTION: &str = r#"{"object":"billing_subscription","hard_limit_usd":20}"#;
+const BILLING_USAGE: &str = r#"{"object":"list","total_usage":250}"#;
+const USER_SELF: &str =
+    r#"{"success":true,"message":"","data":{"quota":8750000,"used_quota":1250000}}"#;
+const DASHBOARD_USAGE: &str = r#"{"remaining_credit_in_usd":7.5,"credit_limit_in_usd":10}"#;
+
+#[test]
+fn unknown_relay_probes_packycode_first() {
+    let server = StubServer::start(Duration::ZERO);
+    let home = setup("relay-packycode", &server, "quota", "");
+
+    let output = render(&home, &[]);
+
+    // 自定义域名的 PackyCode：users/info 成功后不再请求 /v1/dashboard/usage
+    assert_eq!(server.paths(), vec!["/api/backend/users/info".to_string()]);
+    assert!(output.contains("2.50"), "unexpected output: {:?}", output);
+}
+
+#[test]
+fn unknown_relay_falls_back_to_generic() {
+    let server = StubServer::with_routes(
+        Duration::ZERO,
+        &[
+            ("/api/backend/users/info", NOT_FOUND),
+            ("/v1/dashboard/usage", DASHBOARD_USAGE),
+        ],
+    );
+    let home = setup("relay-generic", &server, "quota", "");
+
+    let output = render(&home, &[]);
+
+    assert_eq!(
+        server.paths(),
+        vec!["/api/backend/users/info", "/v1/dashboard/usage"]
+    );
+    assert!(output.contains("2.50"), "unexpected output: {:?}", output);
+}
+
+#[test]
+fn configured_provider_overrides_detection() {
+    let server =
+        StubServer::with_routes(Duration::ZERO, &[("/v1/dashboard/usage", DASHBOARD_USAGE)]);
+    let home = setup(
+        "explicit",
+        &server,
+        "quota",
+        "[quota]\nprovider = \"packycode\"",
+    );
+
+    let output = render(&home, &[]);
+
+    // 没有 JWT 时不请求排名
+    assert_eq!(server.paths(), vec!["/api/backend/users/info".to_string()]);
+    assert!(output.contains("2.50"), "unexpected output: {:?}", output);
+}
+
+#[test]
+fn custom_provider_maps_fields() {
+    let server = StubServer::with_routes(Duration::ZERO, &[("/api/balance", BALANCE)]);
+    let config = format!(
+        "[quota.custom]\nurl = \"{}/api/balance\"\nmethod = \"POST\"\nauth_header = \"X-Token: {{api_key}}\"\nspent = \"/data/used\"\nbudget = \"/data/total\"\ncurrency = \"/data/currency\"",
+        server.url
+    );
+    let home = setup("custom", &server, "quota", &config);
+
+    let output = render(&home, &[]);
+
+    assert_eq!(server.paths(), vec!["/api/balance".to_string()]);
+    assert!(output.contains("¥3.25"), "unexpected output: {:?}", output);
+}
+
+#[test]
+fn custom_provider_caches_per_hardcoded_token() {
+    let server = StubServer::with_routes(Duration::ZERO, &[("/api/balance", BALANCE)]);
+    let config = format!(
+        "[quota.custom]\nurl = \"{}/api/balance\"\nauth_header = \"X-Token: first\"\nspent = \"/data/used\"\nbudget = \"/data/total\"",
+        server.url
+    );
+    let home = setup("custom-token", &server, "quota", &config);
+
+    render(&home, &[]);
+    render(&home, &[]);
+    assert_eq!(server.requests(), 1);
+
+    // 只换写死的 token，不能读到上一个 token 的缓存
+    let path = home.join("config.toml");
+    let config = fs::read_to_string(&path).unwrap();
+    fs::write(&path, config.replace("X-Token: first", "X-Token: second")).unwrap();
+    render(&home, &[]);
+    assert_eq!(server.requests(), 2);
+}
+
+#[test]
+fn oneapi_billing_converts_cents() {
+    let server = StubServer::with_routes(
+        Duration::ZERO,
+        &[
+            ("/dashboard/billing/subscription", SUBSCRIPTION),
+            ("/dashboard/billing/usage", BILLING_USAGE),
+        ],
+    );
+    let home = setup(
+        "oneapi-billing",
+        &server,
+        "quota",
+        "[quota]\nprovider = \"oneapi\"",
+    );
+
+    let output = render(&home, &[]);
+
+    let mut paths = server.paths();
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec![
+            "/dashboard/billing/subscription",
+            "/dashboard/billing/usage"
+        ]
+    );
+    // 额度 $20，累计用量 250 美分：按余额显示
+    assert!(
+        output.contains("Balance: $17.50"),
+        "unexpected output: {:?}",
+        output
+    );
+}
+
+#[test]
+fn oneapi_user_converts_quota_units() {
+    let server = StubServer::with_routes(Duration::ZERO, &[("/api/user/self", USER_SELF)]);
+    // 设置 access_token 后 auto 选择 one-api
+    let home = setup(
+        "oneapi-user",
+        &server,
+        "quota",
+        "[quota.oneapi]\nmode = \"user\"\naccess_token = \"access\"\nuser_id = 1",
+    );
+
+    let output = render(&home, &[]);
+
+    assert_eq!(server.paths(), vec!["/api/user/self".to_string()]);
+    // 剩余 8750000 / 500000 = $17.50
+    assert!(
+        output.contains("Balance: $17.50"),
+        "unexpected output: {:?}",
+        output
+    );
+}
+
+/// Cost report with an older bucket and today's, amounts in cents
+fn cost_report() -> String {
+    let today = chrono::Utc::now().format("%Y-%m-%dT00:00:00Z");
+    format!(
+        r#"{{"data":[{{"starting_at":"2000-01-01T00:00:00Z","ending_at":"2000-01-02T00:00:00Z","results":[{{"currency":"USD","amount":"500","workspace_id":"wrkspc_team"}}]}},{{"starting_at":"{}","ending_at":"{}","results":[{{"currency":"USD","amount":"250.0","workspace_id":"wrkspc_team"}},{{"currency":"USD","amount":"100","workspace_id":null}}]}}],"has_more":false,"next_page":null}}"#,
+        today, today
+    )
+}
+
+/// Today's usage report: the team workspace and the Default workspace (`null`)
+const USAGE_REPORT: &str = r#"{"data":[{"starting_at":"2026-01-01T00:00:00Z","ending_at":"2026-01-02T00:00:00Z","results":[{"uncached_input_tokens":1000,"cache_creation":{"ephemeral_1h_input_tokens":0,"ephemeral_5m_input_tokens":200},"cache_read_input_tokens":5000,"output_tokens":300,"workspace_id":"wrkspc_team"},{"uncached_input_tokens":40,"cache_creation":{"ephemeral_1h_input_tokens":0,"ephemeral_5m_input_tokens":0},"cache_read_input_tokens":0,"output_tokens":2,"workspace_id":null}]}],"has_more":false,"next_page":null}"#;
+
+/// Render with the anthropic provider limited to `workspace_id`
+fn render_anthropic(name: &str, workspace_id: &str) -> (String, Vec<String>) {
+    let report = cost_report();
+    let server = StubServer::with_routes(
+        Duration::ZERO,
+        &[
+            ("/v1/organizations/cost_report", &report),
+            ("/v1/organizations/usage_report/messages", USAGE_REPORT),
+        ],
+    );
+    let config = format!(
+        "[quota]\nprovider = \"anthropic\"\n\n[quota.anthropic]\nadmin_key = \"sk-ant-admin-test\"\nworkspace_id = \"{}\"\nbase_url = \"{}\"\ndaily_budget = 10",
+        workspace_id, server.url
+    );
+    let home = setup(name, &server, "quota", &config);
+    let output = render(&home, &[]);
+    let mut paths = server.paths();
+    paths.sort();
+    (output, paths)
+}
+
+#[test]
+fn anthropic_reports_workspace_cost_and_tokens() {
+    let (output, paths) = render_anthropic("anthropic", "wrkspc_team");
+
+    assert_eq!(paths.len(), 2, "unexpected requests: {:?}", paths);
+    assert!(paths[0].starts_with("/v1/organizations/cost_report?"));
+    assert!(paths[1].starts_with("/v1/organizations/usage_report/messages?"));
+    for path in &paths {
+        assert!(path.contains("bucket_width=1d"));
+        assert!(path.contains("group_by%5B%5D=workspace_id"));
+    }
+    // 只计入该 workspace 今天的 250 美分与 6500 个 token
+    assert!(
+        output.contains("Today: $2.50 · 6.5k tokens"),
+        "unexpected output: {:?}",
+        output
+    );
+}
+
+#[test]
+fn anthropic_default_workspace_counts_null_results() {
+    let (output, _) = render_anthropic("anthropic-default", "default");
+
+    // Default workspace 在报告中的 workspace_id 为 null：今天 100 美分、42 个 token
+    assert!(
+        output.contains("Today: $1.00 · 42 tokens"),
+        "unexpected output: {:?}",
+        output
+    );
+}
+
+#[test]
+fn anthropic_without_admin_key_is_hidden() {
+    let server = StubServer::start(Duration::ZERO);
+    let home = setup(
+        "anthropic-no-key",
+        &server,
+        "quota",
+        "[quota]\nprovider = \"anthropic\"",
+    );
+
+    let output = render(&home, &[]);
+
+    assert!(server.paths().is_empty());
+    assert!(!output.contains("Quota"), "unexpected output: {:?}", output);
+}
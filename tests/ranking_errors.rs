@@ -0,0 +1,79 @@
+// 排名请求失败时按错误类型说明原因，而不是统一显示 "排名错误"
+mod common;
+
+use common::{jwt, render, setup, StubServer, UNAUTHORIZED};
+use std::time::Duration;
+
+/// Render the ranking segment with a valid token against `server`
+fn render_ranking(name: &str, server: &StubServer) -> String {
+    let home = setup(
+        name,
+        server,
+        "ranking",
+        &format!("[packycode]\nbase_url = \"{}\"", server.url),
+    );
+    let token = jwt(r#"{"username":"tester"}"#);
+    render(&home, &[("PACKYCODE_JWT_TOKEN", token.as_str())])
+}
+
+#[test]
+fn unauthorized_reports_expired_token() {
+    let server = StubServer::with_routes(Duration::ZERO, &[("peer-spending", UNAUTHORIZED)]);
+
+    let output = render_ranking("ranking-401", &server);
+
+    assert!(
+        output.contains("排名错误: token 已过期"),
+        "unexpected output: {:?}",
+        output
+    );
+}
+
+#[test]
+fn slow_server_reports_timeout() {
+    // 单次请求上限为 2 秒
+    let server = StubServer::start(Duration::from_millis(2500));
+
+    let output = render_ranking("ranking-timeout", &server);
+
+    assert!(
+        output.contains("排名错误: 超时"),
+        "unexpected output: {:?}",
+        output
+    );
+}
+
+#[test]
+fn malformed_json_reports_bad_response() {
+    let server = StubServer::with_routes(Duration::ZERO, &[("peer-spending", "{\"peers\":")]);
+
+    let output = render_ranking("ranking-json", &server);
+
+    assert!(
+        output.contains("排名错误: 响应格式错误"),
+        "unexpected output: {:?}",
+        output
+    );
+}
+
+#[test]
+fn errors_are_not_cached() {
+    let server = StubServer::with_routes(Duration::ZERO, &[("peer-spending", UNAUTHORIZED)]);
+    let home = setup(
+        "ranking-retry",
+        &server,
+        "ranking",
+        &format!("[packycode]\nbase_url = \"{}\"", server.url),
+    );
+    let token = jwt(r#"{"username":"tester"}"#);
+
+    render(&home, &[("PACKYCODE_JWT_TOKEN", token.as_str())]);
+    render(&home, &[("PACKYCODE_JWT_TOKEN", token.as_str())]);
+
+    let peer_requests = server
+        .paths()
+        .iter()
+        .filter(|path| path.contains("peer-spending"))
+        .count();
+    assert_eq!(peer_requests, 2);
+}